@@ -5,7 +5,23 @@ Modernized with builder patterns, async support, and comprehensive error handlin
 
 # Import the Rust extension classes and expose them at the package level
 # These are the direct bindings to the Rust code
-from ._rust_importer import RustCedarAuthorizer, RustCedarPolicy, RustCedarPolicySet
+from ._rust_importer import (
+    RustCedarAuthorizationError,
+    RustCedarAuthorizer,
+    RustCedarEntities,
+    RustCedarEntityBuilder,
+    RustCedarEntityUid,
+    RustCedarJsonError,
+    RustCedarLimitError,
+    RustCedarParseError,
+    RustCedarPolicy,
+    RustCedarPolicySet,
+    RustCedarResponse,
+    RustCedarSchema,
+    RustCedarSchemaError,
+    RustCedarTemplate,
+    RustPolicyStore,
+)
 
 # Import async API components
 from .async_api import (
@@ -16,6 +16,20 @@ try:
     RustCedarPolicy = _rust.CedarPolicy
     RustCedarPolicySet = _rust.CedarPolicySet
     RustCedarAuthorizer = _rust.CedarAuthorizer
+    RustCedarSchema = _rust.CedarSchema
+    RustCedarTemplate = _rust.CedarTemplate
+    RustCedarEntities = _rust.CedarEntities
+    RustPolicyStore = _rust.PolicyStore
+    RustCedarEntityUid = _rust.CedarEntityUid
+    RustCedarResponse = _rust.CedarResponse
+    RustCedarEntityBuilder = _rust.CedarEntityBuilder
+
+    # Typed exceptions raised by the extension, all ValueError subclasses
+    RustCedarJsonError = _rust.CedarJsonError
+    RustCedarParseError = _rust.CedarParseError
+    RustCedarAuthorizationError = _rust.CedarAuthorizationError
+    RustCedarSchemaError = _rust.CedarSchemaError
+    RustCedarLimitError = _rust.CedarLimitError
 
 except ImportError:
     # If the import fails, it likely means the Rust extension has not been built.
@@ -31,5 +45,38 @@ except ImportError:
     RustCedarPolicy = MockRustClass
     RustCedarPolicySet = MockRustClass
     RustCedarAuthorizer = MockRustClass
+    RustCedarSchema = MockRustClass
+    RustCedarTemplate = MockRustClass
+    RustCedarEntities = MockRustClass
+    RustPolicyStore = MockRustClass
+    RustCedarEntityUid = MockRustClass
+    RustCedarResponse = MockRustClass
+    RustCedarEntityBuilder = MockRustClass
+
+    # Exceptions stay exception types so `except` clauses still work; nothing raises them
+    class MockRustError(ValueError):
+        pass
+
+    RustCedarJsonError = MockRustError
+    RustCedarParseError = MockRustError
+    RustCedarAuthorizationError = MockRustError
+    RustCedarSchemaError = MockRustError
+    RustCedarLimitError = MockRustError
 
-__all__ = ["RustCedarPolicy", "RustCedarPolicySet", "RustCedarAuthorizer"]
+__all__ = [
+    "RustCedarPolicy",
+    "RustCedarPolicySet",
+    "RustCedarAuthorizer",
+    "RustCedarSchema",
+    "RustCedarTemplate",
+    "RustCedarEntities",
+    "RustPolicyStore",
+    "RustCedarEntityUid",
+    "RustCedarResponse",
+    "RustCedarEntityBuilder",
+    "RustCedarJsonError",
+    "RustCedarParseError",
+    "RustCedarAuthorizationError",
+    "RustCedarSchemaError",
+    "RustCedarLimitError",
+]
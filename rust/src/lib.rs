@@ -1,11 +1,25 @@
 use cedar_policy::{
-    Context, Decision, Entities, EntityUid, Policy, PolicySet, Request,
+    ActionConstraint, Context, Decision, Effect, Entities, EntityUid, Policy, PolicySet,
+    PrincipalConstraint, Request, RequestBuilder, ResourceConstraint, Response, Schema,
+    SchemaFragment, SlotId, Template, ValidationMode, Validator,
 };
+use miette::Diagnostic;
 use pyo3::prelude::*;
-use pyo3::exceptions::PyValueError;
+use pyo3::create_exception;
+use pyo3::types::{
+    PyBool, PyBytes, PyDict, PyFloat, PyFrozenSet, PyInt, PyIterator, PyList, PySet, PyString, PyTuple,
+};
+use pyo3::exceptions::{PyIOError, PyValueError};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::convert::From;
 use std::str::FromStr;
+#[cfg(feature = "test-hooks")]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 use serde_json::Value as JsonValue;
+use sha3::{Digest, Sha3_256};
 use regex::Regex;
 
 /// Extract policy ID from Cedar source code with @id annotation
@@ -16,10 +30,7 @@ fn extract_policy_id_from_cedar_source(policy_str: &str) -> Option<cedar_policy:
         if let Some(id_match) = captures.get(1) {
             let id_str = id_match.as_str();
             // Create PolicyId from string
-            match cedar_policy::PolicyId::from_str(id_str) {
-                Ok(policy_id) => Some(policy_id),
-                Err(_) => None,
-            }
+            cedar_policy::PolicyId::from_str(id_str).ok()
         } else {
             None
         }
@@ -28,12 +39,873 @@ fn extract_policy_id_from_cedar_source(policy_str: &str) -> Option<cedar_policy:
     }
 }
 
+/// Extract policy ID from the `id` annotation of a policy in Cedar's JSON format
+fn extract_policy_id_from_json(policy: &JsonValue) -> Option<cedar_policy::PolicyId> {
+    policy["annotations"]["id"].as_str().map(cedar_policy::PolicyId::new)
+}
+
+/// Walk the bytes of Cedar source that are outside string literals and `//` comments.
+///
+/// Stops at, and returns the offset of, the first byte for which `visit` returns true.
+fn scan_code(src: &str, mut visit: impl FnMut(usize, u8) -> bool) -> Option<usize> {
+    let bytes = src.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b if visit(i, b) => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Whether `text` ends with the `permit` or `forbid` keyword (not an annotation name)
+fn ends_with_effect_keyword(text: &str) -> bool {
+    ["permit", "forbid"].iter().any(|keyword| {
+        text.strip_suffix(keyword).is_some_and(|rest| {
+            !rest.ends_with(|c: char| c == '@' || c == '_' || c.is_alphanumeric())
+        })
+    })
+}
+
+/// Extract the `when`/`unless` clauses from a single policy's Cedar source
+fn condition_source(src: &str) -> String {
+    let mut depth = 0usize;
+    let mut in_scope = false;
+    let scope_close = scan_code(src, |i, b| match b {
+        b'(' => {
+            if depth == 0 {
+                in_scope = ends_with_effect_keyword(src[..i].trim_end());
+            }
+            depth += 1;
+            false
+        }
+        b')' => {
+            depth = depth.saturating_sub(1);
+            depth == 0 && in_scope
+        }
+        _ => false,
+    });
+    let Some(scope_close) = scope_close else {
+        return String::new();
+    };
+    let rest = &src[scope_close + 1..];
+
+    let mut braces = 0usize;
+    let terminator = scan_code(rest, |_, b| match b {
+        b'{' => {
+            braces += 1;
+            false
+        }
+        b'}' => {
+            braces = braces.saturating_sub(1);
+            false
+        }
+        b';' => braces == 0,
+        _ => false,
+    })
+    .unwrap_or(rest.len());
+    rest[..terminator].trim().to_string()
+}
+
+/// Largest policy, schema, or entity input the constructors accept, in bytes; 0 is unlimited
+static MAX_INPUT_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Raise `CedarLimitError` if `input` exceeds the `set_max_input_bytes` limit
+fn check_input_size(what: &str, input: &[u8]) -> Result<(), CedarError> {
+    match MAX_INPUT_BYTES.load(Ordering::Relaxed) {
+        limit if limit > 0 && input.len() > limit => Err(CedarError::LimitError(format!(
+            "{} input is {} bytes, over the limit of {} bytes",
+            what,
+            input.len(),
+            limit
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Whether `guard_parse` makes every parser panic, set by `_inject_parse_panics` in tests
+#[cfg(feature = "test-hooks")]
+static INJECT_PARSE_PANICS: AtomicBool = AtomicBool::new(false);
+
+/// Run a parser over untrusted input, reporting a panic as `CedarParseError`.
+///
+/// The parsers shouldn't panic, but if malformed input triggers a bug, the
+/// panic must not reach Python as a `PanicException`, which isn't an
+/// `Exception` subclass and so escapes ordinary error handling.
+fn guard_parse<T, E>(what: &str, parse: impl FnOnce() -> Result<T, E>) -> Result<T, E>
+where
+    E: From<CedarError>,
+{
+    let guarded = || {
+        #[cfg(feature = "test-hooks")]
+        if INJECT_PARSE_PANICS.load(Ordering::Relaxed) {
+            std::panic::resume_unwind(Box::new("injected parser panic"));
+        }
+        parse()
+    };
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(guarded)).unwrap_or_else(|_| {
+        let message = format!("Internal error while parsing {}; input rejected", what);
+        Err(CedarError::ParseError(message).into())
+    })
+}
+
+/// Parse an entity uid, naming the request component it was supplied for in errors
+fn parse_entity_uid(uid: &str, role: &str) -> Result<EntityUid, CedarError> {
+    guard_parse("entity uid", || {
+        EntityUid::from_str(uid)
+            .map_err(|e| CedarError::ParseError(format!("Invalid {}: {}", role, e)))
+    })
+}
+
+/// Parse template slot values keyed by slot name (`"?principal"`, `"?resource"`)
+fn parse_slot_values(values: HashMap<String, String>) -> Result<HashMap<SlotId, EntityUid>, CedarError> {
+    let mut slots = HashMap::new();
+    for (slot, uid) in values {
+        let slot_id = match slot.trim_start_matches('?') {
+            "principal" => SlotId::principal(),
+            "resource" => SlotId::resource(),
+            other => return Err(CedarError::ParseError(format!("Unknown template slot: ?{}", other))),
+        };
+        slots.insert(slot_id, parse_entity_uid(&uid, &slot)?);
+    }
+    Ok(slots)
+}
+
+/// Parse an optional context JSON record, defaulting to an empty context.
+///
+/// When a schema and action are given, attribute values are interpreted using
+/// the action's declared context type.
+fn parse_context(
+    context_json: Option<&str>,
+    schema: Option<(&Schema, &EntityUid)>,
+) -> Result<Context, CedarError> {
+    match context_json {
+        Some(json_str) => guard_parse("context", || {
+            check_input_size("Context", json_str.as_bytes())?;
+            let json_val = parse_json(json_str, "Invalid context JSON")?;
+            Context::from_json_value(json_val, schema)
+                .map_err(|e| CedarError::JsonError(format!("Failed to create context: {}", e)))
+        }),
+        None => Ok(Context::empty()),
+    }
+}
+
+/// Longest prefix of the input quoted in JSON parse errors
+const JSON_PREVIEW_CHARS: usize = 80;
+
+/// Parse JSON text, quoting a truncated preview of the input on failure so
+/// the offending document can be identified, e.g. in a batch
+fn parse_json(input: &str, message: &str) -> Result<JsonValue, CedarError> {
+    serde_json::from_str(input).map_err(|e| {
+        let mut preview: String = input.chars().take(JSON_PREVIEW_CHARS).collect();
+        if input.chars().nth(JSON_PREVIEW_CHARS).is_some() {
+            preview.push_str("...");
+        }
+        CedarError::JsonError(format!("{}: {} (input: {:?})", message, e, preview))
+    })
+}
+
+/// Parse an optional entities JSON document, defaulting to an empty store
+fn parse_entities(entities_json: Option<&str>) -> Result<Entities, CedarError> {
+    match entities_json {
+        Some(json_str) => guard_parse("entities", || {
+            check_input_size("Entities", json_str.as_bytes())?;
+            let document = parse_json(json_str, "Failed to parse entities JSON")?;
+            Entities::from_json_value(document, None)
+                .map_err(|e| CedarError::JsonError(format!("Failed to parse entities JSON: {}", e)))
+        }),
+        None => Ok(Entities::empty()),
+    }
+}
+
+/// Parse entities JSON with extra attributes merged into the given entities,
+/// adding an entity with no parents for any uid the document lacks
+fn overlay_attributes(
+    entities_json: Option<&str>,
+    mut overrides: HashMap<EntityUid, JsonValue>,
+) -> Result<Entities, CedarError> {
+    let mut document: JsonValue = match entities_json {
+        Some(json_str) => {
+            check_input_size("Entities", json_str.as_bytes())?;
+            parse_json(json_str, "Failed to parse entities JSON")?
+        },
+        None => JsonValue::Array(Vec::new()),
+    };
+    let Some(entities) = document.as_array_mut() else {
+        return Err(CedarError::JsonError("Entities JSON must be a list".to_string()));
+    };
+
+    for entity in entities.iter_mut() {
+        let Some(uid) = entity.get("uid").and_then(|u| EntityUid::from_json(u.clone()).ok()) else {
+            continue;
+        };
+        if let Some(JsonValue::Object(extra)) = overrides.remove(&uid) {
+            if !entity["attrs"].is_object() {
+                entity["attrs"] = JsonValue::Object(serde_json::Map::new());
+            }
+            if let Some(attrs) = entity["attrs"].as_object_mut() {
+                attrs.extend(extra);
+            }
+        }
+    }
+    for (uid, attrs) in overrides {
+        let uid_json = serde_json::json!({ "type": uid.type_name().to_string(), "id": uid.id().unescaped() });
+        entities.push(serde_json::json!({ "uid": uid_json, "attrs": attrs, "parents": [] }));
+    }
+
+    guard_parse("entities", || {
+        Entities::from_json_value(document, None)
+            .map_err(|e| CedarError::JsonError(format!("Failed to parse entities JSON: {}", e)))
+    })
+}
+
+/// Add parents from a separately maintained hierarchy to an entity store.
+///
+/// Parents are added to those the entity already has; a uid the store lacks
+/// is added as an entity with no attributes.
+fn overlay_parents(
+    entities: &Entities,
+    mut hierarchy: HashMap<EntityUid, Vec<EntityUid>>,
+) -> Result<Entities, CedarError> {
+    let uid_json = |uid: &EntityUid| {
+        serde_json::json!({ "type": uid.type_name().to_string(), "id": uid.id().unescaped() })
+    };
+    let mut document = entities
+        .to_json_value()
+        .map_err(|e| CedarError::JsonError(format!("Failed to serialize entities: {}", e)))?;
+    let Some(list) = document.as_array_mut() else {
+        return Err(CedarError::JsonError("Entities JSON must be a list".to_string()));
+    };
+
+    for entity in list.iter_mut() {
+        let Some(uid) = entity.get("uid").and_then(|u| EntityUid::from_json(u.clone()).ok()) else {
+            continue;
+        };
+        if let Some(extra) = hierarchy.remove(&uid) {
+            // Cedar always writes a `parents` list when serializing an entity
+            if let Some(parents) = entity["parents"].as_array_mut() {
+                parents.extend(extra.iter().map(uid_json));
+            }
+        }
+    }
+    for (uid, parents) in hierarchy {
+        let parents: Vec<JsonValue> = parents.iter().map(uid_json).collect();
+        list.push(serde_json::json!({ "uid": uid_json(&uid), "attrs": {}, "parents": parents }));
+    }
+
+    guard_parse("entities", || {
+        Entities::from_json_value(document, None)
+            .map_err(|e| CedarError::JsonError(format!("Failed to merge hierarchy: {}", e)))
+    })
+}
+
+/// Extend `context_json` with values read from the request's entities.
+///
+/// `derive` maps context keys to attribute paths rooted at `principal`,
+/// `action`, or `resource`, e.g. `"principal.role"` or
+/// `"resource.owner.dept"` for a record attribute. A path whose entity or
+/// attribute is missing derives nothing, so policies can test the key with
+/// `has`. A key that the supplied context already sets is an error.
+fn derive_context(
+    context_json: Option<&str>,
+    derive: &HashMap<String, String>,
+    entities: &Entities,
+    request: [(&str, &EntityUid); 3],
+) -> Result<String, CedarError> {
+    let mut context = match context_json {
+        Some(json_str) => parse_json(json_str, "Invalid context JSON")?,
+        None => JsonValue::Object(serde_json::Map::new()),
+    };
+    let Some(fields) = context.as_object_mut() else {
+        return Err(CedarError::JsonError("Context JSON must be an object".to_string()));
+    };
+
+    for (key, path) in derive {
+        let mut segments = path.split('.');
+        let root = segments.next().unwrap_or_default();
+        let Some((_, uid)) = request.iter().find(|(name, _)| *name == root) else {
+            return Err(CedarError::JsonError(format!(
+                "Context key '{}' derives from '{}', which must start with principal, action, \
+                 or resource",
+                key, path
+            )));
+        };
+        if fields.contains_key(key) {
+            return Err(CedarError::JsonError(format!(
+                "Context key '{}' is both supplied and derived from '{}'",
+                key, path
+            )));
+        }
+        let Some(entity) = entities.get(uid) else {
+            continue;
+        };
+        let record = entity
+            .to_json_value()
+            .map_err(|e| CedarError::JsonError(format!("Failed to serialize entity {}: {}", uid, e)))?;
+        let mut value = Some(&record["attrs"]);
+        for segment in segments {
+            value = value.and_then(|v| v.get(segment));
+        }
+        if let Some(value) = value {
+            fields.insert(key.clone(), value.clone());
+        }
+    }
+    Ok(context.to_string())
+}
+
+/// Check whether `uid` satisfies `in target`, i.e. is the target itself or one of its descendants
+fn uid_in(entities: &Entities, uid: &EntityUid, target: &EntityUid) -> bool {
+    uid == target || entities.is_ancestor_of(target, uid)
+}
+
+/// Check a policy's scope (ignoring `when`/`unless` conditions) against a request triple.
+///
+/// A `None` principal or resource is unconstrained and matches any scope.
+fn scope_matches(
+    policy: &Policy,
+    principal: Option<&EntityUid>,
+    action: &EntityUid,
+    resource: Option<&EntityUid>,
+    entities: &Entities,
+) -> bool {
+    let principal_ok = principal.is_none_or(|principal| match policy.principal_constraint() {
+        PrincipalConstraint::Any => true,
+        PrincipalConstraint::Eq(uid) => principal == &uid,
+        PrincipalConstraint::In(uid) => uid_in(entities, principal, &uid),
+        PrincipalConstraint::Is(ty) => principal.type_name() == &ty,
+        PrincipalConstraint::IsIn(ty, uid) => {
+            principal.type_name() == &ty && uid_in(entities, principal, &uid)
+        }
+    });
+    let action_ok = match policy.action_constraint() {
+        ActionConstraint::Any => true,
+        ActionConstraint::Eq(uid) => action == &uid,
+        ActionConstraint::In(uids) => uids.iter().any(|uid| uid_in(entities, action, uid)),
+    };
+    let resource_ok = resource.is_none_or(|resource| match policy.resource_constraint() {
+        ResourceConstraint::Any => true,
+        ResourceConstraint::Eq(uid) => resource == &uid,
+        ResourceConstraint::In(uid) => uid_in(entities, resource, &uid),
+        ResourceConstraint::Is(ty) => resource.type_name() == &ty,
+        ResourceConstraint::IsIn(ty, uid) => {
+            resource.type_name() == &ty && uid_in(entities, resource, &uid)
+        }
+    });
+    principal_ok && action_ok && resource_ok
+}
+
+/// Whether a policy's JSON form contains an entity literal `{"type": ..., "id": ...}` equal to `uid`
+fn json_mentions_uid(value: &JsonValue, uid: &EntityUid) -> bool {
+    match value {
+        JsonValue::Object(map) => {
+            let field = |key| map.get(key).and_then(JsonValue::as_str);
+            let is_uid = field("type").is_some_and(|t| t == uid.type_name().to_string())
+                && field("id") == Some(uid.id().unescaped());
+            is_uid || map.values().any(|v| json_mentions_uid(v, uid))
+        },
+        JsonValue::Array(items) => items.iter().any(|v| json_mentions_uid(v, uid)),
+        _ => false,
+    }
+}
+
+/// Collect string literals from a policy's JSON form: `Value` strings and `like` pattern text
+fn collect_string_literals(value: &JsonValue, in_value: bool, out: &mut Vec<String>) {
+    match value {
+        JsonValue::String(text) if in_value => out.push(text.clone()),
+        JsonValue::Object(map) if in_value && map.contains_key("__entity") => {},
+        JsonValue::Object(map) => {
+            for (key, child) in map {
+                match (key.as_str(), child) {
+                    // `like` patterns are one `{"Literal": c}` per character, split by wildcards
+                    ("pattern", JsonValue::Array(parts)) if !in_value => {
+                        let mut segment = String::new();
+                        for part in parts.iter().chain([&JsonValue::Null]) {
+                            match part["Literal"].as_str() {
+                                Some(c) => segment.push_str(c),
+                                None if !segment.is_empty() => out.push(std::mem::take(&mut segment)),
+                                None => {},
+                            }
+                        }
+                    },
+                    _ => collect_string_literals(child, in_value || key == "Value", out),
+                }
+            }
+        },
+        JsonValue::Array(items) => {
+            for item in items {
+                collect_string_literals(item, in_value, out);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Collect `attr` of every `context.attr` access or `context has attr` test in a policy's JSON form
+fn collect_context_keys(value: &JsonValue, out: &mut Vec<String>) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map {
+                if key == "." || key == "has" {
+                    if let (Some("context"), Some(attr)) =
+                        (child["left"]["Var"].as_str(), child["attr"].as_str())
+                    {
+                        out.push(attr.to_string());
+                    }
+                }
+                collect_context_keys(child, out);
+            }
+        },
+        JsonValue::Array(items) => items.iter().for_each(|v| collect_context_keys(v, out)),
+        _ => {},
+    }
+}
+
+/// Functions and methods provided by Cedar's `ipaddr`, `decimal`, and `datetime` extensions
+const EXTENSION_FUNCTIONS: &[&str] = &[
+    "ip", "isIpv4", "isIpv6", "isLoopback", "isMulticast", "isInRange",
+    "decimal", "lessThan", "lessThanOrEqual", "greaterThan", "greaterThanOrEqual",
+    "datetime", "duration", "offset", "durationSince", "toDate", "toTime",
+    "toDays", "toHours", "toMinutes", "toSeconds", "toMilliseconds",
+];
+
+/// Collect the extension functions called in a policy's JSON form.
+///
+/// Calls appear as `{"<name>": [args...]}`; extension values embedded as
+/// literals appear as `{"__extn": {"fn": "<name>", ...}}`.
+fn collect_extension_calls(value: &JsonValue, out: &mut Vec<String>) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, child) in map {
+                if child.is_array() && EXTENSION_FUNCTIONS.contains(&key.as_str()) {
+                    out.push(key.clone());
+                }
+                if key == "__extn" {
+                    if let Some(name) = child["fn"].as_str() {
+                        out.push(name.to_string());
+                    }
+                }
+                collect_extension_calls(child, out);
+            }
+        },
+        JsonValue::Array(items) => items.iter().for_each(|v| collect_extension_calls(v, out)),
+        _ => {},
+    }
+}
+
+/// Copy of a policy set keeping only the policies (static or linked) with the given effect
+fn policies_with_effect(policies: &PolicySet, effect: Effect) -> PolicySet {
+    let mut kept = PolicySet::new();
+    for policy in policies.policies().filter(|p| p.effect() == effect) {
+        // Everything copied comes from a valid set, so adding it again can't fail
+        match (policy.template_id(), policy.template_links()) {
+            (Some(template_id), Some(links)) => {
+                if let (None, Some(template)) = (kept.template(template_id), policies.template(template_id)) {
+                    let _ = kept.add_template(template.clone());
+                }
+                let _ = kept.link(template_id.clone(), policy.id().clone(), links);
+            },
+            _ => {
+                let _ = kept.add(policy.clone());
+            },
+        }
+    }
+    kept
+}
+
+/// A policy set holding just `policy`, with its template when the policy is a link
+fn single_policy_set(policies: &PolicySet, policy: &Policy) -> Result<PolicySet, CedarError> {
+    let mut single = PolicySet::new();
+    let result = match (policy.template_id(), policy.template_links()) {
+        (Some(template_id), Some(links)) => policies
+            .template(template_id)
+            .ok_or_else(|| format!("template {} not found", template_id))
+            .and_then(|template| {
+                single.add_template(template.clone()).map_err(|e| e.to_string())?;
+                single
+                    .link(template_id.clone(), policy.id().clone(), links)
+                    .map_err(|e| e.to_string())
+            }),
+        _ => single.add(policy.clone()).map_err(|e| e.to_string()),
+    };
+    result
+        .map(|_| single)
+        .map_err(|e| CedarError::ParseError(format!("Failed to isolate policy {}: {}", policy.id(), e)))
+}
+
+/// Parse a schema given either as JSON or in the Cedar schema syntax
+fn parse_schema(schema_str: &str) -> Result<Schema, CedarError> {
+    if schema_str.trim_start().starts_with('{') {
+        Schema::from_json_str(schema_str)
+            .map_err(|e| CedarError::SchemaError(format!("Failed to parse schema JSON: {}", e)))
+    } else {
+        Schema::from_cedarschema_str(schema_str)
+            .map(|(schema, _warnings)| schema)
+            .map_err(|e| CedarError::SchemaError(format!("Failed to parse Cedar schema: {}", e)))
+    }
+}
+
+/// The schema in its JSON fragment form, for introspection the `Schema` API doesn't offer
+fn parse_schema_fragment(schema_str: &str) -> Result<JsonValue, CedarError> {
+    if schema_str.trim_start().starts_with('{') {
+        serde_json::from_str(schema_str)
+            .map_err(|e| CedarError::SchemaError(format!("Failed to parse schema JSON: {}", e)))
+    } else {
+        SchemaFragment::from_cedarschema_str(schema_str)
+            .map_err(|e| CedarError::SchemaError(format!("Failed to parse Cedar schema: {}", e)))
+            .and_then(|(fragment, _warnings)| {
+                fragment.to_json_value().map_err(|e| {
+                    CedarError::SchemaError(format!("Failed to convert Cedar schema: {}", e))
+                })
+            })
+    }
+}
+
+/// Resolve common type references until a concrete type declaration is reached
+fn resolve_schema_type<'a>(
+    fragment: &'a JsonValue,
+    namespace: &str,
+    mut ty: &'a JsonValue,
+) -> &'a JsonValue {
+    // Common types can't be cyclic in a schema Cedar accepted, so this terminates
+    loop {
+        let name = match ty["type"].as_str() {
+            Some("EntityOrCommon") => ty["name"].as_str(),
+            Some("Record" | "Set" | "String" | "Long" | "Boolean" | "Entity" | "Extension") => None,
+            other => other,
+        };
+        let Some(name) = name else { return ty };
+        let (ns, id) = match name.rsplit_once("::") {
+            Some((ns, id)) => (ns, id),
+            None => (namespace, name),
+        };
+        let found = [ns, ""]
+            .iter()
+            .find_map(|ns| fragment[*ns]["commonTypes"].get(id));
+        match found {
+            Some(next) => ty = next,
+            None => return ty,
+        }
+    }
+}
+
+/// The record type an action declares for its context, with common types resolved
+fn action_context_type<'a>(fragment: &'a JsonValue, action: &EntityUid) -> &'a JsonValue {
+    let namespace = action.type_name().namespace();
+    let declaration = &fragment[namespace.as_str()]["actions"][action.id().unescaped()];
+    resolve_schema_type(fragment, &namespace, &declaration["appliesTo"]["context"])
+}
+
+/// Names of the attributes an action's declared context record requires
+fn required_context_attributes(fragment: &JsonValue, action: &EntityUid) -> Vec<String> {
+    let context = action_context_type(fragment, action);
+    match context["attributes"].as_object() {
+        Some(attributes) => attributes
+            .iter()
+            .filter(|(_, attr)| attr["required"].as_bool().unwrap_or(true))
+            .map(|(name, _)| name.clone())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Short name for the kind of a JSON value, for type mismatch messages
+fn json_kind(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "Boolean",
+        JsonValue::Number(n) if n.is_i64() => "Long",
+        JsonValue::Number(_) => "non-integer number",
+        JsonValue::String(_) => "String",
+        JsonValue::Array(_) => "Set",
+        JsonValue::Object(_) if is_uid_json(value) => "entity",
+        JsonValue::Object(_) => "Record",
+    }
+}
+
+/// Check a JSON value against a schema type, appending one message per mismatch.
+///
+/// `path` names the value in messages, e.g. `context.device.os`.
+fn check_json_type(
+    fragment: &JsonValue,
+    namespace: &str,
+    ty: &JsonValue,
+    value: &JsonValue,
+    path: &str,
+    out: &mut Vec<String>,
+) {
+    let ty = resolve_schema_type(fragment, namespace, ty);
+    let kind = match ty["type"].as_str() {
+        Some("EntityOrCommon") => ty["name"].as_str().unwrap_or_default(),
+        other => other.unwrap_or_default(),
+    };
+    let expected = match kind {
+        "Boolean" | "Bool" => "Boolean",
+        "Long" => "Long",
+        "String" => "String",
+        "Set" => "Set",
+        "Record" => "Record",
+        "Extension" | "ipaddr" | "decimal" | "datetime" | "duration" => "extension value",
+        _ => "entity",
+    };
+    let matches = match expected {
+        "Record" => value.is_object() && !is_uid_json(value),
+        "extension value" => value.is_string() || value.get("__extn").is_some(),
+        _ => json_kind(value) == expected,
+    };
+    if !matches {
+        out.push(format!("{}: expected {}, got {}", path, expected, json_kind(value)));
+        return;
+    }
+
+    match expected {
+        "Set" => {
+            for (index, item) in value.as_array().into_iter().flatten().enumerate() {
+                let item_path = format!("{}[{}]", path, index);
+                check_json_type(fragment, namespace, &ty["element"], item, &item_path, out);
+            }
+        },
+        "Record" => check_record_type(fragment, namespace, ty, value, path, out),
+        _ => {},
+    }
+}
+
+/// Check a JSON object against a schema record type: missing, mistyped, and undeclared attributes
+fn check_record_type(
+    fragment: &JsonValue,
+    namespace: &str,
+    record: &JsonValue,
+    value: &JsonValue,
+    path: &str,
+    out: &mut Vec<String>,
+) {
+    let empty = serde_json::Map::new();
+    let declared = record["attributes"].as_object().unwrap_or(&empty);
+    let supplied = value.as_object().unwrap_or(&empty);
+    for (name, attr_type) in declared {
+        let attr_path = format!("{}.{}", path, name);
+        match supplied.get(name) {
+            Some(attr) => check_json_type(fragment, namespace, attr_type, attr, &attr_path, out),
+            None if attr_type["required"].as_bool().unwrap_or(true) => {
+                out.push(format!("{}: missing required attribute", attr_path));
+            },
+            None => {},
+        }
+    }
+    if !record["additionalAttributes"].as_bool().unwrap_or(false) {
+        for name in supplied.keys().filter(|name| !declared.contains_key(*name)) {
+            out.push(format!("{}.{}: attribute not declared in schema", path, name));
+        }
+    }
+}
+
+/// Split an entities document into entities of schema-declared types, with
+/// undeclared attributes removed, and entities of types the schema doesn't know
+fn split_undeclared(document: JsonValue, schema: &CedarSchema) -> (JsonValue, JsonValue) {
+    let JsonValue::Array(entities) = document else {
+        // Not a list; let Cedar report the problem on the schema-checked side
+        return (document, JsonValue::Array(Vec::new()));
+    };
+    let action_types: Vec<String> = schema
+        .schema
+        .actions()
+        .map(|a| a.type_name().to_string())
+        .collect();
+
+    let (mut declared, mut extra) = (Vec::new(), Vec::new());
+    for mut entity in entities {
+        let uid = entity.get("uid").map(|u| u.get("__entity").unwrap_or(u));
+        let type_name = uid.and_then(|u| u["type"].as_str()).unwrap_or_default().to_string();
+        let (namespace, declaration) = entity_declaration(schema, &type_name);
+
+        if action_types.contains(&type_name) || type_name.is_empty() {
+            declared.push(entity);
+        } else if declaration.is_null() {
+            extra.push(entity);
+        } else {
+            let shape = resolve_schema_type(&schema.fragment, namespace, &declaration["shape"]);
+            if let Some(attrs) = entity.get_mut("attrs").and_then(JsonValue::as_object_mut) {
+                attrs.retain(|attr, _| shape["attributes"].get(attr).is_some());
+            }
+            declared.push(entity);
+        }
+    }
+    (JsonValue::Array(declared), JsonValue::Array(extra))
+}
+
+/// The declaration of an entity type in the schema fragment, with its namespace
+fn entity_declaration<'a>(schema: &'a CedarSchema, type_name: &'a str) -> (&'a str, &'a JsonValue) {
+    let (namespace, name) = type_name.rsplit_once("::").unwrap_or(("", type_name));
+    (namespace, &schema.fragment[namespace]["entityTypes"][name])
+}
+
+/// Fill in attributes an entity omits from their schema `@default("...")` annotations.
+///
+/// Cedar schemas have no native defaults, so the annotation text is read
+/// according to the attribute's declared type: as-is for `String`, parsed for
+/// `Long` and `Bool`, and as entity JSON for anything else.
+fn apply_schema_defaults(document: &mut JsonValue, schema: &CedarSchema) -> Result<(), CedarError> {
+    let Some(entities) = document.as_array_mut() else {
+        return Ok(());
+    };
+    for entity in entities.iter_mut().filter(|e| e.is_object()) {
+        let uid = entity.get("uid").map(|u| u.get("__entity").unwrap_or(u));
+        let type_name = uid.and_then(|u| u["type"].as_str()).unwrap_or_default().to_string();
+        let (namespace, declaration) = entity_declaration(schema, &type_name);
+        let shape = resolve_schema_type(&schema.fragment, namespace, &declaration["shape"]);
+        let Some(declared) = shape["attributes"].as_object() else {
+            continue;
+        };
+
+        for (attr, attr_type) in declared {
+            let Some(default) = attr_type["annotations"]["default"].as_str() else {
+                continue;
+            };
+            if entity["attrs"].get(attr).is_some() {
+                continue;
+            }
+            let resolved = resolve_schema_type(&schema.fragment, namespace, attr_type);
+            let kind = match resolved["type"].as_str() {
+                Some("EntityOrCommon") => resolved["name"].as_str(),
+                other => other,
+            };
+            let invalid = |e: &dyn std::fmt::Display| {
+                CedarError::SchemaError(format!("Invalid @default for {}.{}: {}", type_name, attr, e))
+            };
+            let value = match kind {
+                Some("String") => JsonValue::String(default.to_string()),
+                Some("Long") => JsonValue::from(default.parse::<i64>().map_err(|e| invalid(&e))?),
+                Some("Bool" | "Boolean") => {
+                    JsonValue::Bool(default.parse::<bool>().map_err(|e| invalid(&e))?)
+                },
+                _ => serde_json::from_str(default).map_err(|e| invalid(&e))?,
+            };
+            if !entity["attrs"].is_object() {
+                entity["attrs"] = JsonValue::Object(serde_json::Map::new());
+            }
+            entity["attrs"][attr] = value;
+        }
+    }
+    Ok(())
+}
+
+/// Name of the enum variant in a value's `Debug` output, e.g. `UnexpectedType`
+fn variant_name<T: std::fmt::Debug>(value: &T) -> String {
+    let debug = format!("{:?}", value);
+    debug
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Convert a JSON value into the equivalent Python object
+fn json_to_py(py: Python<'_>, value: &JsonValue) -> PyObject {
+    match value {
+        JsonValue::Null => py.None(),
+        JsonValue::Bool(b) => b.into_py(py),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => i.into_py(py),
+            None => n.as_f64().unwrap_or_default().into_py(py),
+        },
+        JsonValue::String(s) => s.into_py(py),
+        JsonValue::Array(items) => {
+            PyList::new_bound(py, items.iter().map(|v| json_to_py(py, v))).into_py(py)
+        }
+        JsonValue::Object(map) => {
+            let dict = PyDict::new_bound(py);
+            for (k, v) in map {
+                // Setting a str key on a fresh dict cannot fail
+                let _ = dict.set_item(k, json_to_py(py, v));
+            }
+            dict.into_py(py)
+        }
+    }
+}
+
+create_exception!(_rust, CedarJsonError, PyValueError);
+create_exception!(_rust, CedarParseError, PyValueError);
+create_exception!(_rust, CedarAuthorizationError, PyValueError);
+create_exception!(_rust, CedarSchemaError, PyValueError);
+create_exception!(_rust, CedarLimitError, PyValueError);
+
+/// Convert a Python object built from dicts, lists, and scalars into JSON
+fn py_to_json(value: &Bound<'_, PyAny>) -> Result<JsonValue, CedarError> {
+    if value.is_none() {
+        Ok(JsonValue::Null)
+    } else if let Ok(b) = value.downcast::<PyBool>() {
+        Ok(JsonValue::Bool(b.is_true()))
+    } else if let Ok(i) = value.downcast::<PyInt>() {
+        let i: i64 = i
+            .extract()
+            .map_err(|e| CedarError::JsonError(format!("Integer out of range: {}", e)))?;
+        Ok(JsonValue::from(i))
+    } else if let Ok(f) = value.downcast::<PyFloat>() {
+        Ok(JsonValue::from(f.value()))
+    } else if let Ok(s) = value.downcast::<PyString>() {
+        Ok(JsonValue::String(s.to_string()))
+    } else if let Ok(dict) = value.downcast::<PyDict>() {
+        let mut map = serde_json::Map::new();
+        for (k, v) in dict.iter() {
+            let key = k
+                .downcast::<PyString>()
+                .map_err(|_| CedarError::JsonError(format!("Record keys must be strings, got {}", k)))?
+                .to_string();
+            map.insert(key, py_to_json(&v)?);
+        }
+        Ok(JsonValue::Object(map))
+    } else if let Ok(list) = value.downcast::<PyList>() {
+        list.iter().map(|v| py_to_json(&v)).collect::<Result<_, _>>().map(JsonValue::Array)
+    } else if let Ok(tuple) = value.downcast::<PyTuple>() {
+        tuple.iter().map(|v| py_to_json(&v)).collect::<Result<_, _>>().map(JsonValue::Array)
+    } else {
+        Err(CedarError::JsonError(format!(
+            "Unsupported value for JSON conversion: {}",
+            value
+        )))
+    }
+}
+
+/// Recursively merge `overlay` into `base`; records merge key-wise, anything else is replaced
+fn deep_merge(base: &mut JsonValue, overlay: JsonValue) {
+    match (base, overlay) {
+        (JsonValue::Object(base_map), JsonValue::Object(overlay_map)) => {
+            for (k, v) in overlay_map {
+                match base_map.get_mut(&k) {
+                    Some(existing) => deep_merge(existing, v),
+                    None => {
+                        base_map.insert(k, v);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 enum CedarError {
     JsonError(String),
     ParseError(String),
     AuthorizationError(String),
     SchemaError(String),
+    LimitError(String),
 }
 
 impl std::fmt::Display for CedarError {
@@ -43,14 +915,25 @@ impl std::fmt::Display for CedarError {
             CedarError::ParseError(s) => write!(f, "Parse Error: {}", s),
             CedarError::AuthorizationError(s) => write!(f, "Authorization Error: {}", s),
             CedarError::SchemaError(s) => write!(f, "Schema Error: {}", s),
+            CedarError::LimitError(s) => write!(f, "Limit Error: {}", s),
         }
     }
 }
 
-/// Convert Cedar errors to Python exceptions
+/// Convert Cedar errors to Python exceptions.
+///
+/// Each variant maps to its own exception class; all of them subclass
+/// `ValueError` so existing `except ValueError` handlers keep working.
 impl From<CedarError> for PyErr {
     fn from(err: CedarError) -> PyErr {
-        PyValueError::new_err(err.to_string())
+        let message = err.to_string();
+        match err {
+            CedarError::JsonError(_) => CedarJsonError::new_err(message),
+            CedarError::ParseError(_) => CedarParseError::new_err(message),
+            CedarError::AuthorizationError(_) => CedarAuthorizationError::new_err(message),
+            CedarError::SchemaError(_) => CedarSchemaError::new_err(message),
+            CedarError::LimitError(_) => CedarLimitError::new_err(message),
+        }
     }
 }
 
@@ -62,26 +945,70 @@ struct CedarPolicy {
 
 #[pymethods]
 impl CedarPolicy {
+    /// Parse a policy from Cedar source or JSON.
+    ///
+    /// An explicit `policy_id` takes precedence over any `@id` annotation
+    /// (the `id` entry of `annotations` in JSON).
+    ///
+    /// `format` is `"cedar"`, `"json"`, or `"auto"` (the default), which
+    /// treats input starting with `{` as JSON. An explicit `"json"` also
+    /// tolerates a leading byte-order mark.
     #[new]
-    fn new(policy_str: &str) -> PyResult<Self> {
-        // Detect if the input is JSON or Cedar source format
-        let is_json = policy_str.trim_start().starts_with('{');
+    #[pyo3(signature = (policy_str, policy_id=None, format="auto"))]
+    fn new(policy_str: &str, policy_id: Option<&str>, format: &str) -> PyResult<Self> {
+        check_input_size("Policy", policy_str.as_bytes())?;
+        guard_parse("policy", || {
+            let is_json = match format {
+                "auto" => policy_str.trim_start().starts_with('{'),
+                "json" => true,
+                "cedar" => false,
+                other => {
+                    return Err(PyValueError::new_err(format!(
+                        "format must be 'cedar', 'json', or 'auto', got '{}'",
+                        other
+                    )))
+                },
+            };
+            let explicit_id = policy_id.map(cedar_policy::PolicyId::new);
         
-        let policy = if is_json {
-            // Parse as JSON (original behavior)
-            let json_value: JsonValue = serde_json::from_str(policy_str)
-                .map_err(|e| PyValueError::new_err(format!("Failed to parse policy JSON: {}", e)))?;
-            
-            Policy::from_json(None, json_value)
-                .map_err(|e| PyValueError::new_err(format!("Failed to create policy from JSON: {}", e)))?
-        } else {
-            // Parse as Cedar source code with proper ID handling
-            // If the policy has @id annotation, use that, otherwise let Cedar auto-generate
-            let policy_id = extract_policy_id_from_cedar_source(policy_str);
-            Policy::parse(policy_id, policy_str)
-                .map_err(|e| PyValueError::new_err(format!("Failed to parse Cedar policy: {}", e)))?
-        };
+            let policy = if is_json {
+                // Parse as JSON (original behavior)
+                let json_str = policy_str.trim_start_matches('\u{feff}');
+                let json_value: JsonValue = serde_json::from_str(json_str)
+                    .map_err(|e| PyValueError::new_err(format!("Failed to parse policy JSON: {}", e)))?;
+                let policy_id = explicit_id.or_else(|| extract_policy_id_from_json(&json_value));
+
+                Policy::from_json(policy_id, json_value)
+                    .map_err(|e| PyValueError::new_err(format!("Failed to create policy from JSON: {}", e)))?
+            } else {
+                // Parse as Cedar source code with proper ID handling
+                // If the policy has @id annotation, use that, otherwise let Cedar auto-generate
+                let policy_id = explicit_id.or_else(|| extract_policy_id_from_cedar_source(policy_str));
+                Policy::parse(policy_id, policy_str)
+                    .map_err(|e| PyValueError::new_err(format!("Failed to parse Cedar policy: {}", e)))?
+            };
+
+            Ok(CedarPolicy { policy })
+        })
+    }
 
+    /// Build a policy from a dict holding Cedar's JSON policy format.
+    ///
+    /// The dict is converted directly, without a round trip through a JSON
+    /// string. The id is `policy_id` if given, else the dict's `id`
+    /// annotation, else Cedar's default.
+    #[staticmethod]
+    #[pyo3(signature = (policy, policy_id=None))]
+    fn from_json_dict(policy: &Bound<'_, PyDict>, policy_id: Option<&str>) -> PyResult<Self> {
+        let json_value = py_to_json(policy.as_any())?;
+        check_input_size("Policy", json_value.to_string().as_bytes())?;
+        let id = policy_id
+            .map(cedar_policy::PolicyId::new)
+            .or_else(|| extract_policy_id_from_json(&json_value));
+        let policy = guard_parse("policy", move || {
+            Policy::from_json(id, json_value)
+                .map_err(|e| PyValueError::new_err(format!("Failed to create policy from JSON: {}", e)))
+        })?;
         Ok(CedarPolicy { policy })
     }
 
@@ -89,159 +1016,4005 @@ impl CedarPolicy {
     fn id(&self) -> &str {
         self.policy.id().as_ref()
     }
-}
-
-/// Python wrapper for Cedar PolicySet
-#[pyclass(name = "CedarPolicySet")]
-struct CedarPolicySet {
-    policies: PolicySet,
-}
 
-#[pymethods]
-impl CedarPolicySet {
-    #[new]
-    fn new() -> Self {
-        Self {
-            policies: PolicySet::new(),
+    /// The policy's `when`/`unless` clauses as Cedar source, or `""` if it has none.
+    ///
+    /// Policies parsed from Cedar text keep their original formatting; JSON
+    /// policies are rendered by Cedar first.
+    fn condition(&self) -> String {
+        if !self.policy.has_non_scope_constraint() {
+            return String::new();
         }
+        condition_source(&self.policy.to_string())
     }
 
-    fn add(&mut self, policy: &CedarPolicy) -> PyResult<()> {
-        let policy_id_str = policy.policy.id().to_string();
-        self.policies.add(policy.policy.clone()).map_err(|e| {
-            PyValueError::new_err(format!(
-                "Failed to add policy with id '{}'. Cedar error: {}",
-                policy_id_str, e
-            ))
-        })
+    /// A copy of this policy under `new_id`; the original keeps its id
+    fn with_id(&self, new_id: &str) -> Self {
+        CedarPolicy {
+            policy: self.policy.new_id(cedar_policy::PolicyId::new(new_id)),
+        }
     }
 
-    fn __repr__(&self) -> PyResult<String> {
-        // Count policies manually since policies() returns an iterator without len()
-        let count = self.policies.policies().count();
-        Ok(format!("CedarPolicySet({} policies)", count))
+    fn __repr__(&self) -> String {
+        format!("CedarPolicy(id='{}', effect='{}')", self.policy.id(), self.policy.effect())
     }
-}
 
-/// Python wrapper for Cedar Authorizer
-#[pyclass(name = "CedarAuthorizer")]
-struct CedarAuthorizer {
-    authorizer: cedar_policy::Authorizer,
-}
+    /// The policy's Cedar source
+    fn __str__(&self) -> String {
+        self.policy.to_string()
+    }
 
-#[pymethods]
-impl CedarAuthorizer {
-    #[new]
-    fn new() -> Self {
-        CedarAuthorizer {
-            authorizer: cedar_policy::Authorizer::new(),
-        }
+    /// The principal constraint as `{"op": ...}` plus `entity` and/or `entity_type`.
+    ///
+    /// `op` is `"any"`, `"eq"`, `"in"`, `"is"`, or `"is_in"`.
+    fn principal_scope(&self, py: Python<'_>) -> PyObject {
+        let (op, entity_type, entity) = match self.policy.principal_constraint() {
+            PrincipalConstraint::Any => ("any", None, None),
+            PrincipalConstraint::Eq(uid) => ("eq", None, Some(uid)),
+            PrincipalConstraint::In(uid) => ("in", None, Some(uid)),
+            PrincipalConstraint::Is(ty) => ("is", Some(ty), None),
+            PrincipalConstraint::IsIn(ty, uid) => ("is_in", Some(ty), Some(uid)),
+        };
+        json_to_py(py, &scope_json(op, entity_type, entity))
     }
 
-    /// Authorize a request
-    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
-    fn is_authorized(
-        &self,
-        policy_set: &CedarPolicySet,
-        principal: &str,
-        action: &str,
-        resource: &str,
-        context_json: Option<&str>,
-        entities_json: Option<&str>,
-    ) -> PyResult<bool> {
-        let principal_uid = EntityUid::from_str(principal)
-            .map_err(|e| CedarError::ParseError(format!("Invalid principal: {}", e)))?;
-        let action_uid = EntityUid::from_str(action)
-            .map_err(|e| CedarError::ParseError(format!("Invalid action: {}", e)))?;
-        let resource_uid = EntityUid::from_str(resource)
-            .map_err(|e| CedarError::ParseError(format!("Invalid resource: {}", e)))?;
-
-        let context = match context_json {
-            Some(json_str) => {
-                let json_val: JsonValue = serde_json::from_str(json_str)
-                    .map_err(|e| CedarError::JsonError(format!("Invalid context JSON: {}", e)))?;
-                Context::from_json_value(json_val, None)
-                    .map_err(|e| CedarError::JsonError(format!("Failed to create context: {}", e)))?
+    /// The action constraint as `{"op": ..., "actions": [...]}`.
+    ///
+    /// `op` is `"any"` (no actions), `"eq"` for `action == Action::"x"`,
+    /// `"in"` for a list `action in [...]`, or `"in_group"` for
+    /// `action in Action::"group"`, whose one action is the group.
+    fn action_scope(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let (op, actions): (&str, Vec<String>) = match self.policy.action_constraint() {
+            ActionConstraint::Any => ("any", Vec::new()),
+            ActionConstraint::Eq(uid) => ("eq", vec![uid.to_string()]),
+            ActionConstraint::In(uids) => {
+                // Cedar parses `in Action::"g"` and `in [Action::"g"]` alike; only the
+                // JSON form remembers which was written
+                let est = self.policy.to_json().map_err(|e| {
+                    let id = self.policy.id();
+                    CedarError::ParseError(format!("Failed to convert policy {}: {}", id, e))
+                })?;
+                let op = if est["action"].get("entity").is_some() { "in_group" } else { "in" };
+                (op, uids.iter().map(|u| u.to_string()).collect())
             },
-            None => Context::empty(),
         };
+        Ok(json_to_py(py, &serde_json::json!({ "op": op, "actions": actions })))
+    }
 
-        let entities = match entities_json {
-            Some(json_str) => Entities::from_json_str(json_str, None)
-                .map_err(|e| CedarError::JsonError(format!("Failed to parse entities JSON: {}", e)))?,
-            None => Entities::empty(),
+    /// The resource constraint, in the same shape as `principal_scope`
+    fn resource_scope(&self, py: Python<'_>) -> PyObject {
+        let (op, entity_type, entity) = match self.policy.resource_constraint() {
+            ResourceConstraint::Any => ("any", None, None),
+            ResourceConstraint::Eq(uid) => ("eq", None, Some(uid)),
+            ResourceConstraint::In(uid) => ("in", None, Some(uid)),
+            ResourceConstraint::Is(ty) => ("is", Some(ty), None),
+            ResourceConstraint::IsIn(ty, uid) => ("is_in", Some(ty), Some(uid)),
         };
+        json_to_py(py, &scope_json(op, entity_type, entity))
+    }
 
-        let request = Request::new(
-            principal_uid,
-            action_uid,
-            resource_uid,
-            context,
-            None, // No schema
-        ).map_err(|e| CedarError::ParseError(format!("Failed to create request: {}", e)))?;
-
-        let response = self.authorizer.is_authorized(&request, &policy_set.policies, &entities);
+    /// Names of the extension functions (`ip`, `decimal`, `datetime`, ...) the policy calls, sorted
+    fn uses_extensions(&self) -> PyResult<Vec<String>> {
+        let est = self.policy.to_json().map_err(|e| {
+            CedarError::ParseError(format!("Failed to convert policy {}: {}", self.policy.id(), e))
+        })?;
+        let mut names = Vec::new();
+        collect_extension_calls(&est, &mut names);
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
 
-        Ok(response.decision() == Decision::Allow)
+    /// The integer in the policy's `@priority("N")` annotation, or `None`.
+    ///
+    /// Priorities don't affect authorization; they are a display and sorting
+    /// convention. A non-integer value raises `CedarParseError`.
+    fn priority(&self) -> PyResult<Option<i64>> {
+        Ok(policy_priority(&self.policy)?)
     }
 
-    /// Authorize a request and get a detailed response
-    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
-    fn is_authorized_detailed(
-        &self,
-        policy_set: &CedarPolicySet,
-        principal: &str,
-        action: &str,
-        resource: &str,
-        context_json: Option<&str>,
-        entities_json: Option<&str>,
-    ) -> PyResult<(bool, Vec<String>, Vec<String>)> {
-        let principal_uid = EntityUid::from_str(principal)
-            .map_err(|e| CedarError::ParseError(format!("Invalid principal: {}", e)))?;
-        let action_uid = EntityUid::from_str(action)
-            .map_err(|e| CedarError::ParseError(format!("Invalid action: {}", e)))?;
-        let resource_uid = EntityUid::from_str(resource)
-            .map_err(|e| CedarError::ParseError(format!("Invalid resource: {}", e)))?;
-
-        let context = match context_json {
-            Some(json_str) => {
-                let json_val: JsonValue = serde_json::from_str(json_str)
-                    .map_err(|e| CedarError::JsonError(format!("Invalid context JSON: {}", e)))?;
-                Context::from_json_value(json_val, None)
-                    .map_err(|e| CedarError::JsonError(format!("Failed to create context: {}", e)))?
-            },
-            None => Context::empty(),
-        };
+    /// Top-level context attributes the policy reads or tests with `has`, sorted
+    fn context_keys(&self) -> PyResult<Vec<String>> {
+        let est = self.policy.to_json().map_err(|e| {
+            CedarError::ParseError(format!("Failed to convert policy {}: {}", self.policy.id(), e))
+        })?;
+        let mut keys = Vec::new();
+        collect_context_keys(&est["conditions"], &mut keys);
+        keys.sort();
+        keys.dedup();
+        Ok(keys)
+    }
+}
 
-        let entities = match entities_json {
-            Some(json_str) => Entities::from_json_str(json_str, None)
-                .map_err(|e| CedarError::JsonError(format!("Failed to parse entities JSON: {}", e)))?,
-            None => Entities::empty(),
-        };
+/// A principal or resource constraint as Cedar-like text, e.g. `in Group::"staff"` or `any`
+fn scope_text(
+    entity_type: Option<cedar_policy::EntityTypeName>,
+    op: &str,
+    entity: Option<EntityUid>,
+) -> String {
+    match (entity_type, entity) {
+        (None, None) => "any".to_string(),
+        (None, Some(uid)) => format!("{} {}", op, uid),
+        (Some(ty), None) => format!("is {}", ty),
+        (Some(ty), Some(uid)) => format!("is {} in {}", ty, uid),
+    }
+}
+
+/// One "who can do what" row for `policy`, with each scope rendered by `scope_text`
+fn coverage_row(policy: &Policy) -> JsonValue {
+    let principal = match policy.principal_constraint() {
+        PrincipalConstraint::Any => scope_text(None, "", None),
+        PrincipalConstraint::Eq(uid) => scope_text(None, "==", Some(uid)),
+        PrincipalConstraint::In(uid) => scope_text(None, "in", Some(uid)),
+        PrincipalConstraint::Is(ty) => scope_text(Some(ty), "", None),
+        PrincipalConstraint::IsIn(ty, uid) => scope_text(Some(ty), "", Some(uid)),
+    };
+    let resource = match policy.resource_constraint() {
+        ResourceConstraint::Any => scope_text(None, "", None),
+        ResourceConstraint::Eq(uid) => scope_text(None, "==", Some(uid)),
+        ResourceConstraint::In(uid) => scope_text(None, "in", Some(uid)),
+        ResourceConstraint::Is(ty) => scope_text(Some(ty), "", None),
+        ResourceConstraint::IsIn(ty, uid) => scope_text(Some(ty), "", Some(uid)),
+    };
+    let action = match policy.action_constraint() {
+        ActionConstraint::Any => "any".to_string(),
+        ActionConstraint::Eq(uid) => format!("== {}", uid),
+        ActionConstraint::In(uids) => {
+            let uids: Vec<String> = uids.iter().map(|u| u.to_string()).collect();
+            format!("in [{}]", uids.join(", "))
+        },
+    };
+    let condition = if policy.has_non_scope_constraint() {
+        condition_source(&policy.to_string())
+    } else {
+        String::new()
+    };
+    serde_json::json!({
+        "id": policy.id().to_string(),
+        "effect": policy.effect().to_string(),
+        "principal": principal,
+        "action": action,
+        "resource": resource,
+        "condition": condition,
+    })
+}
+
+/// A principal or resource constraint as its entity type and `(is_in, entity)` parts
+type ScopeParts = (Option<cedar_policy::EntityTypeName>, Option<(bool, EntityUid)>);
+
+fn principal_parts(policy: &Policy) -> ScopeParts {
+    match policy.principal_constraint() {
+        PrincipalConstraint::Any => (None, None),
+        PrincipalConstraint::Eq(uid) => (None, Some((false, uid))),
+        PrincipalConstraint::In(uid) => (None, Some((true, uid))),
+        PrincipalConstraint::Is(ty) => (Some(ty), None),
+        PrincipalConstraint::IsIn(ty, uid) => (Some(ty), Some((true, uid))),
+    }
+}
+
+fn resource_parts(policy: &Policy) -> ScopeParts {
+    match policy.resource_constraint() {
+        ResourceConstraint::Any => (None, None),
+        ResourceConstraint::Eq(uid) => (None, Some((false, uid))),
+        ResourceConstraint::In(uid) => (None, Some((true, uid))),
+        ResourceConstraint::Is(ty) => (Some(ty), None),
+        ResourceConstraint::IsIn(ty, uid) => (Some(ty), Some((true, uid))),
+    }
+}
+
+/// Whether every entity matching `inner` also matches `outer`, judged from the scopes alone.
+///
+/// Without an entity store, `in G` is only known to cover `G` itself and
+/// other `in G` constraints, so this may miss coverage but never invents it.
+fn scope_covers(outer: &ScopeParts, inner: &ScopeParts) -> bool {
+    let type_ok = match (&outer.0, inner) {
+        (None, _) => true,
+        (Some(ty), (Some(inner_ty), _)) => ty == inner_ty,
+        (Some(ty), (None, Some((false, uid)))) => uid.type_name() == ty,
+        _ => false,
+    };
+    let entity_ok = match (&outer.1, &inner.1) {
+        (None, _) => true,
+        (Some((false, uid)), Some((false, inner_uid))) => uid == inner_uid,
+        (Some((true, group)), Some((_, inner_uid))) => group == inner_uid,
+        _ => false,
+    };
+    type_ok && entity_ok
+}
+
+/// Whether every action matching `inner` also matches `outer`, judged from the scopes alone
+fn action_covers(outer: &ActionConstraint, inner: &ActionConstraint) -> bool {
+    match (outer, inner) {
+        (ActionConstraint::Any, _) => true,
+        (ActionConstraint::Eq(a), ActionConstraint::Eq(b)) => a == b,
+        (ActionConstraint::In(group), ActionConstraint::Eq(b)) => group.contains(b),
+        (ActionConstraint::In(group), ActionConstraint::In(inner)) => {
+            inner.iter().all(|b| group.contains(b))
+        },
+        _ => false,
+    }
+}
+
+/// Whether `outer` applies to every request `inner` applies to.
+///
+/// `outer`'s scope must cover `inner`'s, and `outer` must be unconditional
+/// or have exactly the same `when`/`unless` source.
+fn policy_covers(outer: &Policy, inner: &Policy) -> bool {
+    let conditions_ok = !outer.has_non_scope_constraint()
+        || condition_source(&outer.to_string()) == condition_source(&inner.to_string());
+    conditions_ok
+        && scope_covers(&principal_parts(outer), &principal_parts(inner))
+        && action_covers(&outer.action_constraint(), &inner.action_constraint())
+        && scope_covers(&resource_parts(outer), &resource_parts(inner))
+}
+
+/// `partition_by_namespace` bucket for policies spanning several namespaces or none
+const SHARED_NAMESPACE_BUCKET: &str = "*";
+
+/// Entity types named in a policy's JSON form, from uid literals and `is` checks
+fn collect_entity_types(value: &JsonValue, out: &mut BTreeSet<String>) {
+    match value {
+        JsonValue::Object(map) => {
+            if let (Some(JsonValue::String(ty)), Some(_)) = (map.get("type"), map.get("id")) {
+                out.insert(ty.clone());
+            }
+            if let Some(JsonValue::String(ty)) = map.get("entity_type") {
+                out.insert(ty.clone());
+            }
+            map.values().for_each(|v| collect_entity_types(v, out));
+        },
+        JsonValue::Array(items) => items.iter().for_each(|v| collect_entity_types(v, out)),
+        _ => {},
+    }
+}
+
+/// The one namespace a policy's JSON form references, or the shared bucket
+fn policy_namespace(est: &JsonValue) -> String {
+    let mut types = BTreeSet::new();
+    collect_entity_types(est, &mut types);
+    let namespaces: BTreeSet<&str> = types.iter().map(|ty| split_namespace(ty).0).collect();
+    match namespaces.iter().next() {
+        Some(namespace) if namespaces.len() == 1 => namespace.to_string(),
+        _ => SHARED_NAMESPACE_BUCKET.to_string(),
+    }
+}
+
+/// A policy's `@priority("N")` annotation as an integer, if present.
+///
+/// Cedar ignores the annotation; it only orders policies for display.
+fn policy_priority(policy: &Policy) -> Result<Option<i64>, CedarError> {
+    policy
+        .annotation("priority")
+        .map(|value| {
+            value.trim().parse().map_err(|_| {
+                CedarError::ParseError(format!(
+                    "Policy {} has a non-integer @priority: {:?}",
+                    policy.id(),
+                    value
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Python wrapper for a Cedar policy Template
+#[pyclass(name = "CedarTemplate")]
+#[derive(Clone)]
+struct CedarTemplate {
+    template: Template,
+}
+
+#[pymethods]
+impl CedarTemplate {
+    /// Parse a template from Cedar source, using its `@id` annotation when present
+    #[new]
+    #[pyo3(signature = (template_str, template_id=None))]
+    fn new(template_str: &str, template_id: Option<&str>) -> PyResult<Self> {
+        check_input_size("Template", template_str.as_bytes())?;
+        guard_parse("template", || {
+            let id = template_id
+                .map(cedar_policy::PolicyId::new)
+                .or_else(|| extract_policy_id_from_cedar_source(template_str));
+            let template = Template::parse(id, template_str)
+                .map_err(|e| CedarError::ParseError(format!("Failed to parse Cedar template: {}", e)))?;
+            Ok(CedarTemplate { template })
+        })
+    }
+
+    /// Report slot problems in a template source without raising.
+    ///
+    /// Returns an empty list for a well-formed template. Otherwise each entry
+    /// describes one problem, such as a slot used in a condition, an unknown
+    /// slot like `?context`, or source with no slots at all.
+    #[staticmethod]
+    fn check(template_str: &str) -> PyResult<Vec<String>> {
+        check_input_size("Template", template_str.as_bytes())?;
+        guard_parse("template", || {
+            Ok(match Template::parse(None, template_str) {
+                Ok(_) => Vec::new(),
+                Err(errors) => errors.iter().map(|e| e.to_string()).collect(),
+            })
+        })
+    }
+
+    #[getter]
+    fn id(&self) -> &str {
+        self.template.id().as_ref()
+    }
+
+    /// Slot names used by the template, e.g. `["?principal"]`
+    fn slots(&self) -> Vec<String> {
+        let mut slots: Vec<String> = self.template.slots().map(|s| s.to_string()).collect();
+        slots.sort();
+        slots
+    }
+
+    /// Where each slot is used, as `{slot: {"location": ..., "op": ...}}`.
+    ///
+    /// `location` is `"principal_scope"` or `"resource_scope"` (Cedar only
+    /// allows slots in the scope) and `op` is the constraint using the slot:
+    /// `"eq"`, `"in"`, or `"is_in"`, as in `CedarPolicy.principal_scope`.
+    fn slot_usage(&self, py: Python<'_>) -> PyObject {
+        let principal_op = match self.template.principal_constraint() {
+            cedar_policy::TemplatePrincipalConstraint::Eq(None) => Some("eq"),
+            cedar_policy::TemplatePrincipalConstraint::In(None) => Some("in"),
+            cedar_policy::TemplatePrincipalConstraint::IsIn(_, None) => Some("is_in"),
+            _ => None,
+        };
+        let resource_op = match self.template.resource_constraint() {
+            cedar_policy::TemplateResourceConstraint::Eq(None) => Some("eq"),
+            cedar_policy::TemplateResourceConstraint::In(None) => Some("in"),
+            cedar_policy::TemplateResourceConstraint::IsIn(_, None) => Some("is_in"),
+            _ => None,
+        };
+
+        let mut usage = serde_json::Map::new();
+        if let Some(op) = principal_op {
+            usage.insert(
+                SlotId::principal().to_string(),
+                serde_json::json!({ "location": "principal_scope", "op": op }),
+            );
+        }
+        if let Some(op) = resource_op {
+            usage.insert(
+                SlotId::resource().to_string(),
+                serde_json::json!({ "location": "resource_scope", "op": op }),
+            );
+        }
+        json_to_py(py, &JsonValue::Object(usage))
+    }
+}
+
+/// Render a principal/resource constraint as `{"op", "entity_type"?, "entity"?}`
+fn scope_json(
+    op: &str,
+    entity_type: Option<cedar_policy::EntityTypeName>,
+    entity: Option<EntityUid>,
+) -> JsonValue {
+    let mut scope = serde_json::json!({ "op": op });
+    if let Some(ty) = entity_type {
+        scope["entity_type"] = JsonValue::String(ty.to_string());
+    }
+    if let Some(uid) = entity {
+        scope["entity"] = JsonValue::String(uid.to_string());
+    }
+    scope
+}
+
+/// Python wrapper for Cedar PolicySet
+#[pyclass(name = "CedarPolicySet")]
+#[derive(Clone)]
+struct CedarPolicySet {
+    /// Shared snapshot of the policies; edits copy on write and
+    /// `reload_from_string` swaps in a new snapshot whole
+    policies: Arc<PolicySet>,
+    /// Most policies (static and linked) the set may hold; `None` is unlimited
+    max_policies: Option<usize>,
+    /// Default schema for schema-aware methods, set by `bind_schema`
+    schema: Option<CedarSchema>,
+}
+
+impl CedarPolicySet {
+    /// Raise `CedarLimitError` if adding one more policy would exceed `max_policies`
+    fn check_capacity(&self) -> Result<(), CedarError> {
+        match self.max_policies {
+            Some(limit) if self.__len__() >= limit => Err(CedarError::LimitError(format!(
+                "Policy set is limited to {} policies",
+                limit
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// `schema` when one is passed, otherwise the schema from `bind_schema`
+    fn schema_or_bound<'a>(
+        &'a self,
+        schema: &'a Option<SchemaArg<'_>>,
+    ) -> PyResult<std::borrow::Cow<'a, CedarSchema>> {
+        match schema {
+            Some(schema) => schema.resolve(),
+            None => self.schema.as_ref().map(std::borrow::Cow::Borrowed).ok_or_else(|| {
+                PyValueError::new_err("No schema given and none bound with bind_schema")
+            }),
+        }
+    }
+
+    /// The current policies; later edits and reloads don't affect the returned snapshot
+    fn snapshot(&self) -> Arc<PolicySet> {
+        Arc::clone(&self.policies)
+    }
+
+    /// Static and linked policies sorted by id, since `PolicySet::policies()` order isn't stable
+    fn sorted_policies(&self) -> Vec<&Policy> {
+        let mut policies: Vec<&Policy> = self.policies.policies().collect();
+        policies.sort_by(|a, b| AsRef::<str>::as_ref(a.id()).cmp(b.id().as_ref()));
+        policies
+    }
+}
+
+#[pymethods]
+impl CedarPolicySet {
+    #[new]
+    fn new() -> Self {
+        Self {
+            policies: Arc::new(PolicySet::new()),
+            max_policies: None,
+            schema: None,
+        }
+    }
+
+    /// Cap the number of policies; later `add`/`link` calls past it raise `CedarLimitError`.
+    ///
+    /// `None` removes the cap. Policies already in the set are kept even if
+    /// they exceed a newly set limit.
+    #[pyo3(signature = (limit))]
+    fn set_max_policies(&mut self, limit: Option<usize>) {
+        self.max_policies = limit;
+    }
+
+    /// Parse a multi-policy Cedar source into a set.
+    ///
+    /// Policies and templates take their id from `@id(...)` when present and
+    /// fall back to Cedar's positional `policyN` ids. Two annotations naming
+    /// the same id raise `CedarParseError` naming it.
+    #[staticmethod]
+    fn from_string(policies_str: &str) -> PyResult<Self> {
+        Self::from_string_with_prefix(policies_str, "")
+    }
+
+    /// Like `from_string`, but prepend `prefix` to every policy and template id.
+    ///
+    /// Useful for namespacing imported policies per tenant, e.g. a prefix of
+    /// `"acme/"` turns `@id("read")` into `acme/read`.
+    #[staticmethod]
+    fn from_string_with_prefix(policies_str: &str, prefix: &str) -> PyResult<Self> {
+        check_input_size("Policy set", policies_str.as_bytes())?;
+        guard_parse("policies", || {
+            let parsed = PolicySet::from_str(policies_str)
+                .map_err(|e| CedarError::ParseError(format!("Invalid Cedar policy syntax: {}", e)))?;
+            let annotated_id = |id: &cedar_policy::PolicyId, annotation: Option<&str>| {
+                let id = annotation.map(str::to_string).unwrap_or_else(|| id.to_string());
+                cedar_policy::PolicyId::new(format!("{}{}", prefix, id))
+            };
+            let duplicate = |id: &cedar_policy::PolicyId| {
+                CedarError::ParseError(format!("Duplicate policy id '{}' in source", id))
+            };
+
+            let mut policies = PolicySet::new();
+            for template in parsed.templates() {
+                let id = annotated_id(template.id(), template.annotation("id"));
+                policies
+                    .add_template(template.new_id(id.clone()))
+                    .map_err(|_| duplicate(&id))?;
+            }
+            for policy in parsed.policies() {
+                let id = annotated_id(policy.id(), policy.annotation("id"));
+                policies.add(policy.new_id(id.clone())).map_err(|_| duplicate(&id))?;
+            }
+            Ok(Self { policies: Arc::new(policies), max_policies: None, schema: None })
+        })
+    }
+
+    /// Replace the whole set with the policies and templates parsed from `policies_str`.
+    ///
+    /// Parsing follows `from_string`. The new policies are built first and
+    /// swapped in as one snapshot, and a parse error leaves the set unchanged.
+    /// Authorizations take their snapshot when called and evaluate without
+    /// holding the GIL, so a reload from another thread can land while one is
+    /// running; it finishes with the policies it started with.
+    /// A source with more policies than `set_max_policies` allows raises
+    /// `CedarLimitError`.
+    fn reload_from_string(&mut self, policies_str: &str) -> PyResult<()> {
+        let reloaded = Self::from_string(policies_str)?;
+        if let Some(limit) = self.max_policies {
+            if reloaded.__len__() > limit {
+                return Err(CedarError::LimitError(format!(
+                    "Policy set is limited to {} policies",
+                    limit
+                ))
+                .into());
+            }
+        }
+        self.policies = reloaded.policies;
+        Ok(())
+    }
+
+    /// Parse a file mixing policies and templates, with a summary of what was found.
+    ///
+    /// Returns `(policy_set, summary)` where `summary` is `{"policies": n,
+    /// "templates": n, "generated_ids": [...]}`; `generated_ids` lists, sorted,
+    /// the ids Cedar assigned to policies and templates without an `@id`.
+    #[staticmethod]
+    fn load(py: Python<'_>, policies_str: &str) -> PyResult<(Self, PyObject)> {
+        let policy_set = Self::from_string(policies_str)?;
+        let mut generated: Vec<String> = policy_set
+            .policies
+            .policies()
+            .filter(|p| p.annotation("id").is_none())
+            .map(|p| p.id().to_string())
+            .chain(
+                policy_set
+                    .policies
+                    .templates()
+                    .filter(|t| t.annotation("id").is_none())
+                    .map(|t| t.id().to_string()),
+            )
+            .collect();
+        generated.sort();
+        let summary = serde_json::json!({
+            "policies": policy_set.policies.policies().count(),
+            "templates": policy_set.policies.templates().count(),
+            "generated_ids": generated,
+        });
+        Ok((policy_set, json_to_py(py, &summary)))
+    }
+
+    /// Add a policy, resolving an id collision according to `on_conflict`.
+    ///
+    /// `"error"` (the default) raises, `"skip"` keeps the existing policy, and
+    /// `"replace"` removes it first. Returns whether the policy was added.
+    #[pyo3(signature = (policy, on_conflict="error"))]
+    fn add(&mut self, policy: &CedarPolicy, on_conflict: &str) -> PyResult<bool> {
+        let policy_id_str = policy.policy.id().to_string();
+        if !matches!(on_conflict, "error" | "skip" | "replace") {
+            return Err(PyValueError::new_err(format!(
+                "on_conflict must be 'error', 'skip', or 'replace', got '{}'",
+                on_conflict
+            )));
+        }
+        let existing = self
+            .policies
+            .policy(policy.policy.id())
+            .map(|p| p.template_id().is_some());
+        match (on_conflict, existing) {
+            ("skip", Some(_)) => return Ok(false),
+            ("replace", Some(linked)) => {
+                let id = policy.policy.id().clone();
+                let policies = Arc::make_mut(&mut self.policies);
+                let removed = if linked {
+                    policies.unlink(id).map(|_| ()).map_err(|e| e.to_string())
+                } else {
+                    policies.remove_static(id).map(|_| ()).map_err(|e| e.to_string())
+                };
+                removed.map_err(|e| {
+                    PyValueError::new_err(format!(
+                        "Failed to replace policy with id '{}'. Cedar error: {}",
+                        policy_id_str, e
+                    ))
+                })?;
+            },
+            _ => {},
+        }
+        if existing.is_none() {
+            self.check_capacity()?;
+        }
+        Arc::make_mut(&mut self.policies).add(policy.policy.clone()).map(|_| true).map_err(|e| {
+            PyValueError::new_err(format!(
+                "Failed to add policy with id '{}'. Cedar error: {}",
+                policy_id_str, e
+            ))
+        })
+    }
+
+    /// Add a policy after validating it, on its own, against the bound schema.
+    ///
+    /// A policy that doesn't conform raises `CedarSchemaError` listing the
+    /// validator's issues and leaves the set unchanged. Raises `ValueError` if
+    /// no schema is bound; id collisions are handled as by `add`.
+    fn add_validated(&mut self, policy: &CedarPolicy) -> PyResult<()> {
+        let id = policy.policy.id().to_string();
+        {
+            let schema = self.schema_or_bound(&None)?;
+            let single = single_policy_set(&self.policies, &policy.policy)?;
+            let validator = Validator::new(schema.schema.clone());
+            let result = validator.validate(&single, ValidationMode::Strict);
+            let errors: Vec<String> = result.validation_errors().map(|e| e.to_string()).collect();
+            if !errors.is_empty() {
+                return Err(CedarError::SchemaError(format!(
+                    "Policy '{}' failed validation: {}",
+                    id,
+                    errors.join("; ")
+                ))
+                .into());
+            }
+        }
+        self.add(policy, "error").map(|_| ())
+    }
+
+    /// Add a template to the set
+    fn add_template(&mut self, template: &CedarTemplate) -> PyResult<()> {
+        let template_id_str = template.template.id().to_string();
+        Arc::make_mut(&mut self.policies).add_template(template.template.clone()).map_err(|e| {
+            PyValueError::new_err(format!(
+                "Failed to add template with id '{}'. Cedar error: {}",
+                template_id_str, e
+            ))
+        })
+    }
+
+    /// Link a template into a new policy.
+    ///
+    /// `values` maps slot names (`"?principal"`, `"?resource"`) to entity uids.
+    fn link(
+        &mut self,
+        template_id: &str,
+        link_id: &str,
+        values: HashMap<String, String>,
+    ) -> PyResult<()> {
+        self.check_capacity()?;
+        Arc::make_mut(&mut self.policies)
+            .link(
+                cedar_policy::PolicyId::new(template_id),
+                cedar_policy::PolicyId::new(link_id),
+                parse_slot_values(values)?,
+            )
+            .map_err(|e| {
+                PyValueError::new_err(format!(
+                    "Failed to link template '{}' as '{}'. Cedar error: {}",
+                    template_id, link_id, e
+                ))
+            })
+    }
+
+    /// Link `template_id` once per entry of `links`, continuing past failures.
+    ///
+    /// Each entry is a dict with `link_id` and `values` (as for `link`).
+    /// Entries that link successfully are kept even if others fail. Returns
+    /// one `{"index", "link_id", "error"}` dict per failed entry, so an empty
+    /// list means every link was added.
+    fn link_many(
+        &mut self,
+        py: Python<'_>,
+        template_id: &str,
+        links: Vec<Bound<'_, PyDict>>,
+    ) -> Vec<PyObject> {
+        let mut failures = Vec::new();
+        for (index, entry) in links.iter().enumerate() {
+            let link_id: Option<String> = entry
+                .get_item("link_id")
+                .ok()
+                .flatten()
+                .and_then(|id| id.extract().ok());
+            let linked = match (&link_id, entry.get_item("values")) {
+                (Some(link_id), Ok(Some(values))) => values
+                    .extract::<HashMap<String, String>>()
+                    .and_then(|values| self.link(template_id, link_id, values)),
+                (None, _) => Err(PyValueError::new_err("entry needs a string 'link_id'")),
+                (Some(_), _) => Err(PyValueError::new_err("entry needs a 'values' dict")),
+            };
+            if let Err(e) = linked {
+                failures.push(json_to_py(
+                    py,
+                    &serde_json::json!({
+                        "index": index,
+                        "link_id": link_id,
+                        "error": e.value_bound(py).to_string(),
+                    }),
+                ));
+            }
+        }
+        failures
+    }
+
+    /// Replace the slot values of an existing linked policy.
+    ///
+    /// The link keeps its id and template. If the new values can't be linked,
+    /// the original link is left in place and the error is raised.
+    fn relink(&mut self, link_id: &str, values: HashMap<String, String>) -> PyResult<()> {
+        let id = cedar_policy::PolicyId::new(link_id);
+        let template_id = match self.policies.policy(&id) {
+            Some(policy) => match policy.template_id() {
+                Some(template_id) => template_id.clone(),
+                None => {
+                    return Err(PyValueError::new_err(format!(
+                        "Policy '{}' is not a template link",
+                        link_id
+                    )))
+                }
+            },
+            None => {
+                return Err(PyValueError::new_err(format!("No linked policy with id '{}'", link_id)))
+            }
+        };
+        let new_slots = parse_slot_values(values)?;
+
+        // Work on a copy so a failed link leaves the original untouched
+        let mut updated = PolicySet::clone(&self.policies);
+        let relink_err = |e: cedar_policy::PolicySetError| {
+            PyValueError::new_err(format!(
+                "Failed to relink '{}' from template '{}'. Cedar error: {}",
+                link_id, template_id, e
+            ))
+        };
+        updated.unlink(id.clone()).map_err(relink_err)?;
+        updated.link(template_id.clone(), id, new_slots).map_err(relink_err)?;
+        self.policies = Arc::new(updated);
+        Ok(())
+    }
+
+    /// Cedar source for each linked policy with its slots filled in, in link id order.
+    ///
+    /// Useful for exporting links as standalone policies, e.g.
+    /// `permit(principal == User::"alice", ...)` for a link of a
+    /// `principal == ?principal` template. A template `@id` annotation is
+    /// rewritten to the link id, so the exported sources load back together.
+    fn resolved_linked_policies(&self) -> PyResult<Vec<String>> {
+        let resolve_err = |id: &cedar_policy::PolicyId, e: String| {
+            CedarError::ParseError(format!("Failed to resolve linked policy {}: {}", id, e))
+        };
+        let mut sources = Vec::new();
+        for policy in self.sorted_policies() {
+            if policy.template_id().is_none() {
+                continue;
+            }
+            let mut est = policy.to_json().map_err(|e| resolve_err(policy.id(), e.to_string()))?;
+            // The template's `@id` would collide across links; name the link instead
+            if let Some(annotations) = est["annotations"].as_object_mut() {
+                if annotations.contains_key("id") {
+                    annotations.insert("id".to_string(), policy.id().to_string().into());
+                }
+            }
+            let resolved = Policy::from_json(Some(policy.id().clone()), est)
+                .map_err(|e| resolve_err(policy.id(), e.to_string()))?;
+            sources.push(resolved.to_string());
+        }
+        Ok(sources)
+    }
+
+    /// Linked policies grouped by template id.
+    ///
+    /// Every template appears, with a list of `{"link_id", "values"}` dicts
+    /// sorted by link id; `values` maps slot names to entity uids.
+    fn links_by_template(&self, py: Python<'_>) -> PyObject {
+        let mut grouped: BTreeMap<String, Vec<JsonValue>> = self
+            .policies
+            .templates()
+            .map(|t| (t.id().to_string(), Vec::new()))
+            .collect();
+        for policy in self.policies.policies() {
+            if let (Some(template_id), Some(links)) = (policy.template_id(), policy.template_links()) {
+                let values: BTreeMap<String, String> = links
+                    .into_iter()
+                    .map(|(slot, uid)| (slot.to_string(), uid.to_string()))
+                    .collect();
+                grouped.entry(template_id.to_string()).or_default().push(serde_json::json!({
+                    "link_id": policy.id().to_string(),
+                    "values": values,
+                }));
+            }
+        }
+        for links in grouped.values_mut() {
+            links.sort_by(|a, b| a["link_id"].as_str().cmp(&b["link_id"].as_str()));
+        }
+        json_to_py(py, &serde_json::json!(grouped))
+    }
+
+    /// Raise `ValueError` naming every template that has no links.
+    ///
+    /// Meant as a pre-serving check for applications that expect each
+    /// template to be instantiated at least once.
+    fn require_all_linked(&self) -> PyResult<()> {
+        let mut unlinked: Vec<String> = self
+            .policies
+            .templates()
+            .filter(|t| !self.policies.policies().any(|p| p.template_id() == Some(t.id())))
+            .map(|t| t.id().to_string())
+            .collect();
+        if unlinked.is_empty() {
+            return Ok(());
+        }
+        unlinked.sort();
+        Err(PyValueError::new_err(format!("Templates with no links: {}", unlinked.join(", "))))
+    }
+
+    /// Remove a static policy by id
+    fn remove(&mut self, policy_id: &str) -> PyResult<()> {
+        let id = cedar_policy::PolicyId::new(policy_id);
+        Arc::make_mut(&mut self.policies).remove_static(id).map(|_| ()).map_err(|e| {
+            PyValueError::new_err(format!(
+                "Failed to remove policy with id '{}'. Cedar error: {}",
+                policy_id, e
+            ))
+        })
+    }
+
+    /// Return an independent copy of this policy set
+    #[pyo3(name = "clone")]
+    fn clone_set(&self) -> Self {
+        self.clone()
+    }
+
+    fn __copy__(&self) -> Self {
+        self.clone()
+    }
+
+    fn __deepcopy__(&self, _memo: &Bound<'_, PyDict>) -> Self {
+        // Policies are immutable values, so a clone is already a deep copy
+        self.clone()
+    }
+
+    fn __len__(&self) -> usize {
+        self.policies.policies().count()
+    }
+
+    /// A "who can do what" table with one row per policy, in id order.
+    ///
+    /// Each row is a dict with `id`, `effect`, `principal`, `action`,
+    /// `resource`, and `condition`. Scopes are rendered as Cedar-like text
+    /// (`any`, `== User::"alice"`, `in Group::"staff"`, `is Photo`,
+    /// `is Photo in Album::"a"`, `in [Action::"read", ...]`); `condition` is
+    /// the `when`/`unless` source, or `""` if there is none. Scopes are
+    /// reported as written; a schema bound with `bind_schema` isn't consulted.
+    fn coverage_report(&self, py: Python<'_>) -> Vec<PyObject> {
+        self.sorted_policies()
+            .into_iter()
+            .map(|policy| json_to_py(py, &coverage_row(policy)))
+            .collect()
+    }
+
+    /// Policy ids ordered by `@priority`, lowest number first.
+    ///
+    /// Policies without a priority come last; ties are broken by id.
+    fn sorted_by_priority(&self) -> PyResult<Vec<String>> {
+        let mut ranked = Vec::new();
+        for policy in self.policies.policies() {
+            let priority = policy_priority(policy)?;
+            ranked.push((priority.is_none(), priority, policy.id().to_string()));
+        }
+        ranked.sort();
+        Ok(ranked.into_iter().map(|(_, _, id)| id).collect())
+    }
+
+    /// Whether the set holds no policies and no templates
+    fn is_empty(&self) -> bool {
+        self.policies.policies().next().is_none() && self.policies.templates().next().is_none()
+    }
+
+    /// Ids of the policies whose scope or conditions mention `uid` as a literal, sorted
+    fn policies_referencing(&self, uid: &str) -> PyResult<Vec<String>> {
+        let uid = parse_entity_uid(uid, "uid")?;
+        let mut ids = Vec::new();
+        for policy in self.policies.policies() {
+            let est = policy.to_json().map_err(|e| {
+                CedarError::ParseError(format!("Failed to convert policy {}: {}", policy.id(), e))
+            })?;
+            if json_mentions_uid(&est, &uid) {
+                ids.push(policy.id().to_string());
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Remove the annotation `key` from every policy and template.
+    ///
+    /// Policies keep their ids and logic; template links are re-created from
+    /// the rebuilt templates with their original slot values.
+    fn strip_annotation(&mut self, key: &str) -> PyResult<()> {
+        let strip = |est: Result<JsonValue, cedar_policy::PolicyToJsonError>, id: &cedar_policy::PolicyId| {
+            let mut est = est.map_err(|e| {
+                CedarError::ParseError(format!("Failed to convert policy {}: {}", id, e))
+            })?;
+            if let Some(annotations) = est.get_mut("annotations").and_then(JsonValue::as_object_mut) {
+                annotations.remove(key);
+            }
+            Ok::<_, CedarError>(est)
+        };
+        let rebuild_err = |id: &cedar_policy::PolicyId, e: String| {
+            CedarError::ParseError(format!("Failed to rebuild policy {}: {}", id, e))
+        };
+
+        let mut rebuilt = PolicySet::new();
+        for template in self.policies.templates() {
+            let est = strip(template.to_json(), template.id())?;
+            let template = Template::from_json(Some(template.id().clone()), est)
+                .map_err(|e| rebuild_err(template.id(), e.to_string()))?;
+            let id = template.id().clone();
+            rebuilt.add_template(template).map_err(|e| rebuild_err(&id, e.to_string()))?;
+        }
+        for policy in self.policies.policies() {
+            let id = policy.id().clone();
+            match (policy.template_id(), policy.template_links()) {
+                (Some(template_id), Some(links)) => rebuilt
+                    .link(template_id.clone(), id.clone(), links)
+                    .map_err(|e| rebuild_err(&id, e.to_string()))?,
+                _ => {
+                    let est = strip(policy.to_json(), &id)?;
+                    let policy = Policy::from_json(Some(id.clone()), est)
+                        .map_err(|e| rebuild_err(&id, e.to_string()))?;
+                    rebuilt.add(policy).map_err(|e| rebuild_err(&id, e.to_string()))?
+                },
+            }
+        }
+        self.policies = Arc::new(rebuilt);
+        Ok(())
+    }
+
+    /// Every distinct string literal used in the policies' conditions, sorted
+    fn string_literals(&self) -> PyResult<Vec<String>> {
+        let mut literals = Vec::new();
+        for policy in self.policies.policies() {
+            let est = policy.to_json().map_err(|e| {
+                CedarError::ParseError(format!("Failed to convert policy {}: {}", policy.id(), e))
+            })?;
+            collect_string_literals(&est["conditions"], false, &mut literals);
+        }
+        literals.sort();
+        literals.dedup();
+        Ok(literals)
+    }
+
+    /// Stable SHA3-256 hex digest of the set's contents.
+    ///
+    /// Each policy and template is hashed by its normalized JSON form, in
+    /// sorted order, so insertion order and source formatting don't matter.
+    /// Ids are left out: an `@id` is part of the hashed annotations, while
+    /// Cedar's positional `policyN` ids would depend on the order policies
+    /// were parsed in.
+    fn content_hash(&self) -> PyResult<String> {
+        let to_json_err = |id: &cedar_policy::PolicyId, e: cedar_policy::PolicyToJsonError| {
+            CedarError::ParseError(format!("Failed to convert policy {}: {}", id, e))
+        };
+        let mut entries = Vec::new();
+        for policy in self.policies.policies() {
+            let est = policy.to_json().map_err(|e| to_json_err(policy.id(), e))?;
+            entries.push(format!("policy\t{}", est));
+        }
+        for template in self.policies.templates() {
+            let est = template.to_json().map_err(|e| to_json_err(template.id(), e))?;
+            entries.push(format!("template\t{}", est));
+        }
+        entries.sort();
+
+        let mut hasher = Sha3_256::new();
+        for entry in &entries {
+            hasher.update(entry.as_bytes());
+            hasher.update(b"\n");
+        }
+        Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Permits made redundant by a broader permit, as `{"shadowed", "shadowing"}` dicts.
+    ///
+    /// A permit is shadowed when another permit's scope covers its scope and
+    /// the other permit is unconditional or has the same conditions, so
+    /// removing it never changes a decision. Two equivalent permits are
+    /// reported once, with the larger id as shadowed. Group membership isn't
+    /// known here, so `in Group::"g"` covers only `g` itself and other
+    /// `in Group::"g"` scopes. Sorted by shadowed id, then shadowing id.
+    fn shadowed_permits(&self, py: Python<'_>) -> Vec<PyObject> {
+        let permits: Vec<&Policy> = self
+            .sorted_policies()
+            .into_iter()
+            .filter(|policy| policy.effect() == Effect::Permit)
+            .collect();
+        let mut pairs = Vec::new();
+        // `permits` is in id order, so comparing positions compares ids
+        for (i, shadowed) in permits.iter().enumerate() {
+            for (j, shadowing) in permits.iter().enumerate() {
+                if i == j || !policy_covers(shadowing, shadowed) {
+                    continue;
+                }
+                if i < j && policy_covers(shadowed, shadowing) {
+                    continue;
+                }
+                pairs.push(json_to_py(
+                    py,
+                    &serde_json::json!({
+                        "shadowed": shadowed.id().to_string(),
+                        "shadowing": shadowing.id().to_string(),
+                    }),
+                ));
+            }
+        }
+        pairs
+    }
+
+    /// Split the set into one set per namespace of the entity types it references.
+    ///
+    /// A policy naming only `App::User`, `App::Action`, and `App::Photo` goes
+    /// under `"App"`, and one naming only unqualified types under `""`.
+    /// Policies spanning several namespaces, or naming no entities at all
+    /// (such as `permit(principal, action, resource)`), go under `"*"`. Links
+    /// stay with their template, which is placed by its own references. Each
+    /// part keeps the bound schema.
+    fn partition_by_namespace(&self) -> PyResult<BTreeMap<String, CedarPolicySet>> {
+        let partition_err = |id: &cedar_policy::PolicyId, e: String| {
+            CedarError::ParseError(format!("Failed to partition policy {}: {}", id, e))
+        };
+        let mut parts: BTreeMap<String, PolicySet> = BTreeMap::new();
+        let mut template_parts = HashMap::new();
+        for template in self.policies.templates() {
+            let est = template.to_json().map_err(|e| partition_err(template.id(), e.to_string()))?;
+            let namespace = policy_namespace(&est);
+            parts
+                .entry(namespace.clone())
+                .or_default()
+                .add_template(template.clone())
+                .map_err(|e| partition_err(template.id(), e.to_string()))?;
+            template_parts.insert(template.id().clone(), namespace);
+        }
+        for policy in self.policies.policies() {
+            let added = match (policy.template_id(), policy.template_links()) {
+                (Some(template_id), Some(links)) => parts
+                    .entry(template_parts[template_id].clone())
+                    .or_default()
+                    .link(template_id.clone(), policy.id().clone(), links)
+                    .map_err(|e| e.to_string()),
+                _ => {
+                    let est = policy.to_json().map_err(|e| partition_err(policy.id(), e.to_string()))?;
+                    parts
+                        .entry(policy_namespace(&est))
+                        .or_default()
+                        .add(policy.clone())
+                        .map_err(|e| e.to_string())
+                },
+            };
+            added.map_err(|e| partition_err(policy.id(), e))?;
+        }
+
+        Ok(parts
+            .into_iter()
+            .map(|(namespace, policies)| {
+                let part = CedarPolicySet {
+                    policies: Arc::new(policies),
+                    max_policies: None,
+                    schema: self.schema.clone(),
+                };
+                (namespace, part)
+            })
+            .collect())
+    }
+
+    /// Whether this set allows every sample request that `other` allows.
+    ///
+    /// Each sample is a dict with `principal`, `action`, and `resource` (uid
+    /// strings or `{"type", "id"}` dicts) and an optional `context` dict, and
+    /// both sets are evaluated against `entities`. Returns `False` as soon as
+    /// a sample allowed by `other` is denied by this set. Requests this set
+    /// allows but `other` denies don't matter.
+    fn permits_superset_of(
+        slf: &Bound<'_, Self>,
+        other: &Bound<'_, CedarPolicySet>,
+        sample_requests: Vec<Bound<'_, PyDict>>,
+        entities: &CedarEntities,
+    ) -> PyResult<bool> {
+        let authorizer = CedarAuthorizer::internal();
+        let (mine, theirs) = (slf.borrow().snapshot(), other.borrow().snapshot());
+        for request in &sample_requests {
+            let request = py_to_json(request.as_any())?;
+            let principal = request_uid(&request, "principal")?;
+            let action = request_uid(&request, "action")?;
+            let resource = request_uid(&request, "resource")?;
+            let context = request.get("context").map(JsonValue::to_string);
+            let allows = |policies: &PolicySet| {
+                authorizer
+                    .evaluate_parsed(
+                        policies,
+                        &principal,
+                        &action,
+                        &resource,
+                        context.as_deref(),
+                        &entities.entities,
+                        None,
+                    )
+                    .map(|response| response.decision() == Decision::Allow)
+            };
+            if allows(&theirs)? && !allows(&mine)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Ids of permits whose principal or resource scope is unconstrained, sorted.
+    ///
+    /// A bare `principal` or `resource` (no `==`, `in`, or `is`) lets the
+    /// permit apply to every entity in that position, leaving any narrowing
+    /// to its conditions; such permits are candidates for tightening.
+    fn unconstrained_permits(&self) -> Vec<String> {
+        self.sorted_policies()
+            .into_iter()
+            .filter(|policy| policy.effect() == Effect::Permit)
+            .filter(|policy| {
+                matches!(policy.principal_constraint(), PrincipalConstraint::Any)
+                    || matches!(policy.resource_constraint(), ResourceConstraint::Any)
+            })
+            .map(|policy| policy.id().to_string())
+            .collect()
+    }
+
+    /// Use `schema` by default in the schema-aware methods.
+    ///
+    /// `validate`, `unreachable_policies`, and `policies_for_action_group`
+    /// fall back to it when no schema is passed, and `add_validated` always
+    /// checks against it. A schema passed explicitly still takes precedence.
+    /// `None` unbinds the schema. Copies of the set keep the bound schema.
+    /// `coverage_report` and the other scope analyses read only the policies
+    /// and ignore the bound schema.
+    #[pyo3(signature = (schema))]
+    fn bind_schema(&mut self, schema: Option<SchemaArg<'_>>) -> PyResult<()> {
+        self.schema = schema.as_ref().map(|s| s.resolve().map(|s| s.into_owned())).transpose()?;
+        Ok(())
+    }
+
+    /// Ids of policies that can never apply to a request valid under `schema`, sorted.
+    ///
+    /// These are the policies Cedar's validator reports as impossible, e.g. a
+    /// scope pairing an action with a resource type it doesn't apply to, or a
+    /// condition that always evaluates to false. `schema` defaults to the
+    /// bound schema.
+    #[pyo3(signature = (schema=None))]
+    fn unreachable_policies(&self, schema: Option<SchemaArg<'_>>) -> PyResult<Vec<String>> {
+        let validator = Validator::new(self.schema_or_bound(&schema)?.schema.clone());
+        let result = validator.validate(&self.policies, ValidationMode::Strict);
+        let mut ids: Vec<String> = result
+            .validation_warnings()
+            .filter(|w| matches!(w, cedar_policy::ValidationWarning::ImpossiblePolicy(_)))
+            .map(|w| w.policy_id().to_string())
+            .collect();
+        ids.sort();
+        ids.dedup();
+        Ok(ids)
+    }
+
+    /// Ids of policies whose action scope covers `group` or any action in it, sorted.
+    ///
+    /// `group` is an action uid such as `Action::"reading"` that the schema
+    /// declares as an action group. Policies with an unconstrained action
+    /// scope are included, since they apply to every member. `schema`
+    /// defaults to the bound schema.
+    #[pyo3(signature = (group, schema=None))]
+    fn policies_for_action_group(
+        &self,
+        group: &str,
+        schema: Option<SchemaArg<'_>>,
+    ) -> PyResult<Vec<String>> {
+        let schema = self.schema_or_bound(&schema)?;
+        let group = parse_entity_uid(group, "action group")?;
+        if !schema.schema.action_groups().any(|g| g == &group) {
+            return Err(PyValueError::new_err(format!("{} is not an action group in the schema", group)));
+        }
+        let hierarchy = schema.schema.action_entities().map_err(|e| {
+            CedarError::SchemaError(format!("Failed to build action hierarchy: {}", e))
+        })?;
+        let members: Vec<&EntityUid> = schema
+            .schema
+            .actions()
+            .chain(std::iter::once(&group))
+            .filter(|action| uid_in(&hierarchy, action, &group))
+            .collect();
+
+        let mut ids: Vec<String> = self
+            .policies
+            .policies()
+            .filter(|policy| {
+                members.iter().any(|action| scope_matches(policy, None, action, None, &hierarchy))
+            })
+            .map(|policy| policy.id().to_string())
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Validate the policies against a schema (a `CedarSchema`, or JSON or Cedar syntax).
+    ///
+    /// Returns one dict per error with `policy_id`, `message`, `kind`, and
+    /// `location` (`{"start": ..., "end": ...}` byte offsets into the policy
+    /// source, or `None` when Cedar doesn't report one). An empty list means
+    /// the policies are valid. `schema` defaults to the bound schema.
+    #[pyo3(signature = (schema=None))]
+    fn validate(&self, py: Python<'_>, schema: Option<SchemaArg<'_>>) -> PyResult<Vec<PyObject>> {
+        let validator = Validator::new(self.schema_or_bound(&schema)?.schema.clone());
+        let result = validator.validate(&self.policies, ValidationMode::Strict);
+
+        Ok(result
+            .validation_errors()
+            .map(|e| {
+                let location = e
+                    .labels()
+                    .and_then(|mut labels| labels.next())
+                    .map(|span| {
+                        serde_json::json!({
+                            "start": span.offset(),
+                            "end": span.offset() + span.len(),
+                        })
+                    });
+                let error = serde_json::json!({
+                    "policy_id": e.policy_id().to_string(),
+                    "message": e.to_string(),
+                    "kind": variant_name(e),
+                    "location": location,
+                });
+                json_to_py(py, &error)
+            })
+            .collect())
+    }
+
+    /// Ids of all static and linked policies, sorted
+    fn policy_ids(&self) -> Vec<String> {
+        self.sorted_policies().iter().map(|p| p.id().to_string()).collect()
+    }
+
+    /// Iterate over the policies as `CedarPolicy` objects, in id order
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        let policies = self
+            .sorted_policies()
+            .into_iter()
+            .map(|policy| Py::new(py, CedarPolicy { policy: policy.clone() }))
+            .collect::<PyResult<Vec<_>>>()?;
+        PyList::new_bound(py, policies).as_any().iter()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        let ids = self.policy_ids();
+        Ok(format!("CedarPolicySet({} policies: {:?})", ids.len(), ids))
+    }
+}
+
+/// Python wrapper for a parsed Cedar Schema
+#[pyclass(name = "CedarSchema")]
+#[derive(Clone)]
+struct CedarSchema {
+    schema: Schema,
+    fragment: JsonValue,
+}
+
+/// A schema argument given either as a parsed `CedarSchema` or as source text
+#[derive(FromPyObject)]
+enum SchemaArg<'py> {
+    Parsed(PyRef<'py, CedarSchema>),
+    Source(String),
+}
+
+impl SchemaArg<'_> {
+    /// The parsed schema, reusing it when one was passed and parsing otherwise
+    fn resolve(&self) -> PyResult<std::borrow::Cow<'_, CedarSchema>> {
+        Ok(match self {
+            SchemaArg::Parsed(schema) => std::borrow::Cow::Borrowed(&**schema),
+            SchemaArg::Source(source) => std::borrow::Cow::Owned(CedarSchema::new(source)?),
+        })
+    }
+}
+
+#[pymethods]
+impl CedarSchema {
+    /// Parse a schema given as JSON or in the Cedar schema syntax
+    #[new]
+    fn new(schema_str: &str) -> PyResult<Self> {
+        check_input_size("Schema", schema_str.as_bytes())?;
+        guard_parse("schema", || {
+            Ok(CedarSchema {
+                schema: parse_schema(schema_str)?,
+                fragment: parse_schema_fragment(schema_str)?,
+            })
+        })
+    }
+
+    /// All action uids declared in the schema, sorted
+    fn actions(&self) -> Vec<String> {
+        let mut actions: Vec<String> = self.schema.actions().map(|a| a.to_string()).collect();
+        actions.sort();
+        actions
+    }
+
+    /// All entity type names declared in the schema, sorted
+    fn entity_types(&self) -> Vec<String> {
+        let mut types: Vec<String> = self.schema.entity_types().map(|t| t.to_string()).collect();
+        types.sort();
+        types
+    }
+
+    /// Check a context dict against the context an action declares.
+    ///
+    /// Returns one message per missing required attribute, mistyped value, or
+    /// undeclared attribute, naming its path (e.g. `context.device.os`); an
+    /// empty list means the context matches. Only the context is checked, not
+    /// the rest of the request.
+    fn validate_context(&self, action: &str, context: &Bound<'_, PyDict>) -> PyResult<Vec<String>> {
+        let action = parse_entity_uid(action, "action")?;
+        if !self.schema.actions().any(|a| a == &action) {
+            return Err(PyValueError::new_err(format!("{} is not declared in the schema", action)));
+        }
+        let namespace = action.type_name().namespace();
+        let declared = action_context_type(&self.fragment, &action);
+        let context = py_to_json(context.as_any())?;
+
+        let mut problems = Vec::new();
+        check_record_type(&self.fragment, &namespace, declared, &context, "context", &mut problems);
+        Ok(problems)
+    }
+
+    /// Actions whose `appliesTo` lists `type_name` as a resource type, sorted
+    fn actions_for_resource_type(&self, type_name: &str) -> PyResult<Vec<String>> {
+        let resource_type = cedar_policy::EntityTypeName::from_str(type_name)
+            .map_err(|e| CedarError::ParseError(format!("Invalid entity type: {}", e)))?;
+        let mut actions: Vec<String> = self
+            .schema
+            .actions()
+            .filter(|action| {
+                self.schema
+                    .resources_for_action(action)
+                    .is_some_and(|mut types| types.any(|t| t == &resource_type))
+            })
+            .map(|action| action.to_string())
+            .collect();
+        actions.sort();
+        Ok(actions)
+    }
+
+    /// Completion candidates for policy editors.
+    ///
+    /// Returns `{"entity_types": [...], "actions": [...], "attributes": {...}}`,
+    /// where `attributes` maps each entity type to its declared attribute names.
+    /// All lists are sorted.
+    fn completions(&self, py: Python<'_>) -> PyObject {
+        let mut attributes = BTreeMap::new();
+        if let Some(namespaces) = self.fragment.as_object() {
+            for (namespace, declarations) in namespaces {
+                let Some(entity_types) = declarations["entityTypes"].as_object() else {
+                    continue;
+                };
+                for (name, declaration) in entity_types {
+                    let shape = resolve_schema_type(&self.fragment, namespace, &declaration["shape"]);
+                    let mut names: Vec<&String> = shape["attributes"]
+                        .as_object()
+                        .map(|attrs| attrs.keys().collect())
+                        .unwrap_or_default();
+                    names.sort();
+                    let type_name = if namespace.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{}::{}", namespace, name)
+                    };
+                    attributes.insert(type_name, names);
+                }
+            }
+        }
+        let completions = serde_json::json!({
+            "entity_types": self.entity_types(),
+            "actions": self.actions(),
+            "attributes": attributes,
+        });
+        json_to_py(py, &completions)
+    }
+}
+
+/// Structured result of an authorization request
+#[pyclass(name = "CedarResponse")]
+#[derive(Clone)]
+struct CedarResponse {
+    /// Whether the decision was `Allow`
+    #[pyo3(get)]
+    allowed: bool,
+    /// `"Allow"` or `"Deny"`
+    #[pyo3(get)]
+    decision: String,
+    /// Ids of the determining policies
+    #[pyo3(get)]
+    reasons: Vec<String>,
+    /// Evaluation errors reported by Cedar
+    #[pyo3(get)]
+    errors: Vec<String>,
+    /// Caller-supplied correlation id, echoed back unchanged
+    #[pyo3(get)]
+    request_id: Option<String>,
+    /// Microseconds spent in Cedar's evaluation, when timing was requested
+    #[pyo3(get)]
+    eval_micros: Option<u64>,
+    /// Number of policies whose scope matched, so their conditions were evaluated
+    #[pyo3(get)]
+    evaluated_conditions: usize,
+}
+
+/// A parsed request with its uids kept for scope matching
+struct PreparedRequest {
+    request: Request,
+    principal: EntityUid,
+    action: EntityUid,
+    resource: EntityUid,
+}
+
+/// Cedar's response for a request with measurements taken while producing it
+struct Evaluation {
+    response: Response,
+    /// Time spent in Cedar's evaluation, excluding request parsing and validation
+    elapsed: Duration,
+    /// Policies whose scope matched the request
+    evaluated_conditions: usize,
+}
+
+impl CedarResponse {
+    fn from_response(response: &Response, request_id: Option<String>) -> Self {
+        let allowed = response.decision() == Decision::Allow;
+        CedarResponse {
+            allowed,
+            decision: if allowed { "Allow" } else { "Deny" }.to_string(),
+            reasons: response.diagnostics().reason().map(|p| p.to_string()).collect(),
+            errors: response.diagnostics().errors().map(|e| e.to_string()).collect(),
+            request_id,
+            eval_micros: None,
+            evaluated_conditions: 0,
+        }
+    }
+
+    /// Build a response from a full evaluation; `timed` includes `eval_micros`
+    fn from_evaluation(evaluation: &Evaluation, request_id: Option<String>, timed: bool) -> Self {
+        CedarResponse {
+            eval_micros: timed.then_some(evaluation.elapsed.as_micros() as u64),
+            evaluated_conditions: evaluation.evaluated_conditions,
+            ..Self::from_response(&evaluation.response, request_id)
+        }
+    }
+}
+
+#[pymethods]
+impl CedarResponse {
+    fn __repr__(&self) -> String {
+        match &self.request_id {
+            Some(id) => format!(
+                "CedarResponse(decision='{}', reasons={:?}, request_id='{}')",
+                self.decision, self.reasons, id
+            ),
+            None => format!(
+                "CedarResponse(decision='{}', reasons={:?})",
+                self.decision, self.reasons
+            ),
+        }
+    }
+}
+
+/// Lock `mutex`; a poisoned lock only means another thread panicked mid-update, and the data is still usable
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Number of parsed uids an authorizer keeps for repeated requests
+const UID_CACHE_CAPACITY: usize = 256;
+
+/// Bounded cache of parsed entity uids, evicting the oldest entry when full
+#[derive(Default)]
+struct UidCache {
+    uids: HashMap<String, EntityUid>,
+    order: VecDeque<String>,
+    hits: u64,
+    misses: u64,
+}
+
+impl UidCache {
+    fn parse(&mut self, uid: &str, role: &str) -> Result<EntityUid, CedarError> {
+        if let Some(parsed) = self.uids.get(uid) {
+            self.hits += 1;
+            return Ok(parsed.clone());
+        }
+        self.misses += 1;
+        let parsed = parse_entity_uid(uid, role)?;
+        if self.order.len() >= UID_CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.uids.remove(&oldest);
+            }
+        }
+        self.order.push_back(uid.to_string());
+        self.uids.insert(uid.to_string(), parsed.clone());
+        Ok(parsed)
+    }
+}
+
+/// Python wrapper for Cedar Authorizer.
+///
+/// Evaluation runs without the GIL, so settings live behind locks rather than
+/// `&mut self`: another thread may change them while an authorization runs.
+#[pyclass(name = "CedarAuthorizer", frozen)]
+struct CedarAuthorizer {
+    authorizer: cedar_policy::Authorizer,
+    /// Reject requests whose principal or resource is missing from the entities
+    require_entities: bool,
+    /// Recently parsed request uids; `None` when caching is disabled
+    uid_cache: Mutex<Option<UidCache>>,
+    /// Authorizations taking longer than this many milliseconds are reported to `slow_callback`
+    slow_ms: Mutex<Option<f64>>,
+    /// Called as `callback(request, elapsed_ms)` for slow authorizations
+    slow_callback: Mutex<Option<PyObject>>,
+    /// Context keys requests may supply, and whether others are rejected (or dropped)
+    context_allowlist: Mutex<Option<(BTreeSet<String>, bool)>>,
+}
+
+impl CedarAuthorizer {
+    /// An authorizer with the default settings, for evaluations done on the caller's behalf
+    fn internal() -> Self {
+        Self::new(false, true, None)
+    }
+
+    /// Apply `set_context_allowlist` to `context_json`, returning the context to evaluate.
+    ///
+    /// `None` means the input can be used unchanged.
+    fn allowed_context(&self, context_json: Option<&str>) -> Result<Option<String>, CedarError> {
+        if let Some(json_str) = context_json {
+            check_input_size("Context", json_str.as_bytes())?;
+        }
+        let context_allowlist = lock(&self.context_allowlist);
+        let (Some((allowlist, reject)), Some(json_str)) = (context_allowlist.as_ref(), context_json) else {
+            return Ok(None);
+        };
+        let mut context = parse_json(json_str, "Invalid context JSON")?;
+        // A non-record context is left for `parse_context` to report
+        let Some(fields) = context.as_object_mut() else {
+            return Ok(None);
+        };
+        let mut unexpected: Vec<String> =
+            fields.keys().filter(|key| !allowlist.contains(*key)).cloned().collect();
+        if unexpected.is_empty() {
+            return Ok(None);
+        }
+        unexpected.sort();
+        if *reject {
+            return Err(CedarError::AuthorizationError(format!(
+                "Context keys not in the allowlist: {}",
+                unexpected.join(", ")
+            )));
+        }
+        fields.retain(|key, _| allowlist.contains(key));
+        Ok(Some(context.to_string()))
+    }
+
+    /// Parse a request uid, going through the cache when it is enabled
+    fn parse_uid(&self, uid: &str, role: &str) -> Result<EntityUid, CedarError> {
+        match lock(&self.uid_cache).as_mut() {
+            Some(cache) => cache.parse(uid, role),
+            None => parse_entity_uid(uid, role),
+        }
+    }
+
+    /// Parse the entities and run Cedar's authorizer on a request against `policies`
+    fn evaluate(
+        &self,
+        policies: &PolicySet,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> Result<Response, CedarError> {
+        let entities = parse_entities(entities_json)?;
+        self.evaluate_parsed(policies, principal, action, resource, context_json, &entities, None)
+    }
+
+    /// Authorize against an already-parsed entity store.
+    ///
+    /// With a schema, the context must supply every attribute the action
+    /// requires, is typed by the action's declaration, and the request is
+    /// validated before evaluation.
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_parsed(
+        &self,
+        policies: &PolicySet,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities: &Entities,
+        schema: Option<&CedarSchema>,
+    ) -> Result<Response, CedarError> {
+        self.evaluate_full(policies, principal, action, resource, context_json, entities, schema)
+            .map(|evaluation| evaluation.response)
+    }
+
+    /// Like `evaluate_parsed`, also measuring the evaluation for `CedarResponse`
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_full(
+        &self,
+        policies: &PolicySet,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities: &Entities,
+        schema: Option<&CedarSchema>,
+    ) -> Result<Evaluation, CedarError> {
+        let prepared = self.prepare(principal, action, resource, context_json, entities, schema)?;
+        Ok(self.run(&prepared, policies, entities))
+    }
+
+    /// Parse a request and run the checks that don't depend on the policies.
+    ///
+    /// `context_json` is the caller's context, subject to
+    /// `set_context_allowlist`. The result can be evaluated against several
+    /// policy sets with `run`.
+    fn prepare(
+        &self,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities: &Entities,
+        schema: Option<&CedarSchema>,
+    ) -> Result<PreparedRequest, CedarError> {
+        let allowed_context = self.allowed_context(context_json)?;
+        let context_json = allowed_context.as_deref().or(context_json);
+        self.prepare_allowed(principal, action, resource, context_json, entities, schema)
+    }
+
+    /// `prepare` for a context that already passed the allowlist, or was built from one that did
+    fn prepare_allowed(
+        &self,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities: &Entities,
+        schema: Option<&CedarSchema>,
+    ) -> Result<PreparedRequest, CedarError> {
+        let principal_uid = self.parse_uid(principal, "principal")?;
+        let action_uid = self.parse_uid(action, "action")?;
+        let resource_uid = self.parse_uid(resource, "resource")?;
+
+        if let Some(schema) = schema {
+            let supplied: JsonValue = match context_json {
+                Some(json_str) => parse_json(json_str, "Invalid context JSON")?,
+                None => JsonValue::Null,
+            };
+            let missing: Vec<String> = required_context_attributes(&schema.fragment, &action_uid)
+                .into_iter()
+                .filter(|name| supplied.get(name).is_none())
+                .collect();
+            if !missing.is_empty() {
+                return Err(CedarError::SchemaError(format!(
+                    "Missing required context attributes for {}: {}",
+                    action_uid,
+                    missing.join(", ")
+                )));
+            }
+        }
+
+        let schema = schema.map(|s| &s.schema);
+        let context = parse_context(context_json, schema.map(|s| (s, &action_uid)))?;
+
+        if self.require_entities {
+            for (role, uid) in [("principal", &principal_uid), ("resource", &resource_uid)] {
+                if entities.get(uid).is_none() {
+                    return Err(CedarError::AuthorizationError(format!(
+                        "{} {} not found in entities",
+                        role, uid
+                    )));
+                }
+            }
+        }
 
         let request = Request::new(
-            principal_uid,
-            action_uid,
-            resource_uid,
+            principal_uid.clone(),
+            action_uid.clone(),
+            resource_uid.clone(),
             context,
-            None, // No schema
+            schema,
         ).map_err(|e| CedarError::ParseError(format!("Failed to create request: {}", e)))?;
+        Ok(PreparedRequest {
+            request,
+            principal: principal_uid,
+            action: action_uid,
+            resource: resource_uid,
+        })
+    }
+
+    /// Evaluate a prepared request against `policies`.
+    ///
+    /// The GIL is released meanwhile, so other threads can authorize or
+    /// reload policy sets; `policies` is a snapshot they can't change.
+    fn run(&self, prepared: &PreparedRequest, policies: &PolicySet, entities: &Entities) -> Evaluation {
+        Python::with_gil(|py| {
+            py.allow_threads(|| {
+                let evaluated_conditions = policies
+                    .policies()
+                    .filter(|p| {
+                        scope_matches(
+                            p,
+                            Some(&prepared.principal),
+                            &prepared.action,
+                            Some(&prepared.resource),
+                            entities,
+                        )
+                    })
+                    .count();
+                let started = Instant::now();
+                let response = self.authorizer.is_authorized(&prepared.request, policies, entities);
+                Evaluation {
+                    response,
+                    elapsed: started.elapsed(),
+                    evaluated_conditions,
+                }
+            })
+        })
+    }
+
+    /// The smallest-id permit that allows a prepared request, or `None` when it's denied.
+    ///
+    /// Forbids are decided in one evaluation. If none applies, the permits
+    /// whose scope matches are evaluated alone in id order, stopping at the
+    /// first one satisfied. The GIL is released throughout, as in `run`.
+    fn first_permit(
+        &self,
+        prepared: &PreparedRequest,
+        policies: &PolicySet,
+        entities: &Entities,
+    ) -> Result<Option<String>, CedarError> {
+        let decide = |policies: &PolicySet| {
+            self.authorizer.is_authorized(&prepared.request, policies, entities)
+        };
+        Python::with_gil(|py| {
+            py.allow_threads(|| {
+                let forbids = policies_with_effect(policies, Effect::Forbid);
+                if decide(&forbids).diagnostics().reason().next().is_some() {
+                    return Ok(None);
+                }
+
+                let mut permits: Vec<&Policy> = policies
+                    .policies()
+                    .filter(|p| p.effect() == Effect::Permit)
+                    .filter(|p| {
+                        scope_matches(
+                            p,
+                            Some(&prepared.principal),
+                            &prepared.action,
+                            Some(&prepared.resource),
+                            entities,
+                        )
+                    })
+                    .collect();
+                permits.sort_by(|a, b| AsRef::<str>::as_ref(a.id()).cmp(b.id().as_ref()));
+                for policy in permits {
+                    if decide(&single_policy_set(policies, policy)?).decision() == Decision::Allow {
+                        return Ok(Some(policy.id().to_string()));
+                    }
+                }
+                Ok(None)
+            })
+        })
+    }
+
+    /// Invoke the slow-authorization callback when `total` exceeds `slow_ms`.
+    ///
+    /// The callback gets the request as a dict and the elapsed milliseconds.
+    /// An exception it raises is reported through `sys.unraisablehook` rather
+    /// than failing the authorization.
+    fn report_if_slow(
+        &self,
+        total: Duration,
+        principal: &str,
+        action: Option<&str>,
+        resource: Option<&str>,
+        context_json: Option<&str>,
+    ) {
+        self.report_request_if_slow(total, principal, action, resource, context_json, None);
+    }
+
+    /// `report_if_slow` for a call made with a `request_id`, which is passed in the request dict
+    fn report_request_if_slow(
+        &self,
+        total: Duration,
+        principal: &str,
+        action: Option<&str>,
+        resource: Option<&str>,
+        context_json: Option<&str>,
+        request_id: Option<&str>,
+    ) {
+        let Some(threshold) = *lock(&self.slow_ms) else {
+            return;
+        };
+        let elapsed_ms = total.as_secs_f64() * 1000.0;
+        if elapsed_ms <= threshold {
+            return;
+        }
+        Python::with_gil(|py| {
+            // Take a reference so the lock isn't held while the callback runs
+            let Some(callback) = lock(&self.slow_callback).as_ref().map(|c| c.clone_ref(py)) else {
+                return;
+            };
+            let context: Option<JsonValue> =
+                context_json.and_then(|json_str| serde_json::from_str(json_str).ok());
+            let request = json_to_py(py, &serde_json::json!({
+                "principal": principal,
+                "action": action,
+                "resource": resource,
+                "context": context,
+                "request_id": request_id,
+            }));
+            if let Err(err) = callback.call1(py, (request, elapsed_ms)) {
+                err.write_unraisable_bound(py, Some(callback.bind(py)));
+            }
+        });
+    }
+}
+
+#[pymethods]
+impl CedarAuthorizer {
+    /// Create an authorizer.
+    ///
+    /// With `require_entities`, requests raise `CedarAuthorizationError` when the
+    /// principal or resource uid is absent from the supplied entities instead of
+    /// silently evaluating against a nonexistent entity.
+    ///
+    /// `cache_uids` keeps a small bounded cache of parsed principal, action,
+    /// and resource uids so hot loops over the same requests skip re-parsing.
+    ///
+    /// `slow_ms` sets the threshold for the callback registered with
+    /// `set_slow_callback`; it can also be changed later through the
+    /// `slow_ms` attribute.
+    #[new]
+    #[pyo3(signature = (require_entities=false, cache_uids=true, slow_ms=None))]
+    fn new(require_entities: bool, cache_uids: bool, slow_ms: Option<f64>) -> Self {
+        CedarAuthorizer {
+            authorizer: cedar_policy::Authorizer::new(),
+            require_entities,
+            uid_cache: Mutex::new(cache_uids.then(UidCache::default)),
+            slow_ms: Mutex::new(slow_ms),
+            slow_callback: Mutex::new(None),
+            context_allowlist: Mutex::new(None),
+        }
+    }
+
+    /// Authorizations taking longer than this many milliseconds are reported to the slow callback
+    #[getter]
+    fn slow_ms(&self) -> Option<f64> {
+        *lock(&self.slow_ms)
+    }
+
+    #[setter]
+    fn set_slow_ms(&self, slow_ms: Option<f64>) {
+        *lock(&self.slow_ms) = slow_ms;
+    }
+
+    /// Accept only the context keys in `keys`; `None` accepts any key again.
+    ///
+    /// A request whose context has another top-level key raises
+    /// `CedarAuthorizationError` naming the keys, or with `reject=False`
+    /// has them dropped before evaluation, so clients can't supply
+    /// attributes that policies trust. Only the supplied context is screened;
+    /// keys `authorize` derives with `derive_context_from` are always kept.
+    #[pyo3(signature = (keys, reject=true))]
+    fn set_context_allowlist(&self, keys: Option<Vec<String>>, reject: bool) {
+        *lock(&self.context_allowlist) = keys.map(|keys| (keys.into_iter().collect(), reject));
+    }
+
+    /// Register `callback(request, elapsed_ms)` for authorizations slower than `slow_ms`.
+    ///
+    /// `request` is a dict with `principal`, `action`, `resource`,
+    /// `context`, and `request_id` (set by the methods taking one); the time
+    /// covers the whole call, and each call is reported at most once however
+    /// many evaluations it runs; a failing `assert_decision` is reported too.
+    /// Parts a call varies over, like `effective_permissions`' actions and
+    /// resources or `authorize_context_matrix`'s contexts, are `None`. Pass
+    /// `None` to remove the callback.
+    fn set_slow_callback(&self, callback: Option<PyObject>) {
+        // Release the lock before dropping the old callback, whose finalizer may run Python code
+        let previous = std::mem::replace(&mut *lock(&self.slow_callback), callback);
+        drop(previous);
+    }
+
+    /// Enable or disable the uid cache; disabling drops any cached uids
+    fn set_uid_cache(&self, enabled: bool) {
+        let mut uid_cache = lock(&self.uid_cache);
+        if !enabled {
+            *uid_cache = None;
+        } else if uid_cache.is_none() {
+            *uid_cache = Some(UidCache::default());
+        }
+    }
+
+    /// Uid cache counters as `{"hits", "misses", "size"}`, or `None` when disabled
+    fn uid_cache_info(&self) -> Option<BTreeMap<&'static str, u64>> {
+        lock(&self.uid_cache).as_ref().map(|cache| {
+            BTreeMap::from([
+                ("hits", cache.hits),
+                ("misses", cache.misses),
+                ("size", cache.uids.len() as u64),
+            ])
+        })
+    }
+
+    /// Authorize a request.
+    ///
+    /// `schema` may be a `CedarSchema` or schema source text; pass a
+    /// `CedarSchema` when authorizing repeatedly to avoid re-parsing it. With a
+    /// schema, the context is typed and the request validated against it.
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None, schema=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn is_authorized(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+        schema: Option<SchemaArg<'_>>,
+    ) -> PyResult<bool> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let response = match schema {
+            Some(schema) => {
+                let entities = parse_entities(entities_json)?;
+                self.evaluate_parsed(
+                    &policies,
+                    principal,
+                    action,
+                    resource,
+                    context_json,
+                    &entities,
+                    Some(&*schema.resolve()?),
+                )?
+            },
+            None => self.evaluate(&policies, principal, action, resource, context_json, entities_json)?,
+        };
+
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok(response.decision() == Decision::Allow)
+    }
+
+    /// Authorize a request and return the full Cedar response as a JSON string, for archival.
+    ///
+    /// The document follows the shape of Cedar's own JSON interface:
+    /// `{"decision": "allow" | "deny", "diagnostics": {"reason": [policy ids],
+    /// "errors": [{"policyId": id, "error": {"message": text}}]}}`. Reasons and
+    /// errors are sorted by policy id so identical decisions archive identically.
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
+    fn is_authorized_archive(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<String> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let response =
+            self.evaluate(&policies, principal, action, resource, context_json, entities_json)?;
+
+        let decision = match response.decision() {
+            Decision::Allow => "allow",
+            Decision::Deny => "deny",
+        };
+        let mut reason: Vec<String> = response.diagnostics().reason().map(|p| p.to_string()).collect();
+        reason.sort();
+        let mut errors: Vec<(String, String)> = response
+            .diagnostics()
+            .errors()
+            .map(|e| match e {
+                cedar_policy::AuthorizationError::PolicyEvaluationError(e) => {
+                    (e.policy_id().to_string(), e.inner().to_string())
+                },
+            })
+            .collect();
+        errors.sort();
+        let errors: Vec<JsonValue> = errors
+            .into_iter()
+            .map(|(id, message)| serde_json::json!({ "policyId": id, "error": { "message": message } }))
+            .collect();
+
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok(serde_json::json!({
+            "decision": decision,
+            "diagnostics": { "reason": reason, "errors": errors },
+        })
+        .to_string())
+    }
+
+    /// A stable SHA3-256 hex key for memoizing the decision on a request.
+    ///
+    /// The key covers the policy set's and entity store's `content_hash`, the
+    /// request uids in normalized form, and the context with its keys sorted,
+    /// so it changes whenever any of them changes and is otherwise unaffected
+    /// by formatting or key order. Authorizer settings and schemas aren't
+    /// included; keep separate caches per configuration.
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities=None))]
+    fn decision_cache_key(
+        &self,
+        policy_set: &CedarPolicySet,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities: Option<&CedarEntities>,
+    ) -> PyResult<String> {
+        let mut context = match context_json {
+            Some(json_str) => {
+                check_input_size("Context", json_str.as_bytes())?;
+                parse_json(json_str, "Invalid context JSON")?
+            },
+            None => JsonValue::Object(serde_json::Map::new()),
+        };
+        sort_json_keys(&mut context);
+        let entities_hash = match entities {
+            Some(entities) => entities.content_hash()?,
+            None => String::new(),
+        };
+        let fields = [
+            ("policies", policy_set.content_hash()?),
+            ("entities", entities_hash),
+            ("principal", self.parse_uid(principal, "principal")?.to_string()),
+            ("action", self.parse_uid(action, "action")?.to_string()),
+            ("resource", self.parse_uid(resource, "resource")?.to_string()),
+            ("context", context.to_string()),
+        ];
+
+        let mut hasher = Sha3_256::new();
+        for (name, value) in &fields {
+            hasher.update(format!("{}\t{}\n", name, value).as_bytes());
+        }
+        Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Authorize against `primary`, consulting `fallback` only on a default deny.
+    ///
+    /// The fallback decides the request when no `primary` policy is satisfied
+    /// and none failed to evaluate. A satisfied forbid in `primary` denies
+    /// without falling through, as does an evaluation error, since the failed
+    /// policy might have been a forbid.
+    #[pyo3(signature = (primary, fallback, principal, action, resource, context_json=None, entities_json=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn is_authorized_with_fallback(
+        &self,
+        primary: &Bound<'_, CedarPolicySet>,
+        fallback: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<bool> {
+        let called = Instant::now();
+        let (primary, fallback) = (primary.borrow().snapshot(), fallback.borrow().snapshot());
+        let entities = parse_entities(entities_json)?;
+        let decide = |policies: &PolicySet| {
+            self.evaluate_parsed(policies, principal, action, resource, context_json, &entities, None)
+        };
+
+        let response = decide(&primary)?;
+        let diagnostics = response.diagnostics();
+        let default_deny = response.decision() == Decision::Deny
+            && diagnostics.reason().next().is_none()
+            && diagnostics.errors().next().is_none();
+        let response = if default_deny { decide(&fallback)? } else { response };
+
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok(response.decision() == Decision::Allow)
+    }
+
+    /// The permit responsible for allowing a request, or `None` when it's denied.
+    ///
+    /// A permit only counts if no forbid overrides it, which is exactly when
+    /// Cedar allows the request. When several permits are satisfied, the one
+    /// with the smallest id is returned.
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
+    fn effective_permit(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<Option<String>> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let response =
+            self.evaluate(&policies, principal, action, resource, context_json, entities_json)?;
+        let permit = match response.decision() {
+            Decision::Allow => response.diagnostics().reason().map(|id| id.to_string()).min(),
+            Decision::Deny => None,
+        };
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok(permit)
+    }
+
+    /// Decide a request, stopping at the first satisfied permit.
+    ///
+    /// The request is parsed once and forbids are evaluated first; if none
+    /// applies, permits whose scope matches are evaluated one at a time, in
+    /// id order, until one is satisfied. The decision always agrees with
+    /// `is_authorized`. Returns `(allowed, permit_id)` where `permit_id` is
+    /// the permit that allowed the request, the same one `effective_permit`
+    /// names, or `None` when denied.
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
+    fn is_authorized_fast(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<(bool, Option<String>)> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let entities = parse_entities(entities_json)?;
+        let prepared = self.prepare(principal, action, resource, context_json, &entities, None)?;
+        let permit = self.first_permit(&prepared, &policies, &entities)?;
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok((permit.is_some(), permit))
+    }
+
+    /// Authorize a request and get a detailed response
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
+    fn is_authorized_detailed(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<(bool, Vec<String>, Vec<String>)> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let response =
+            self.evaluate(&policies, principal, action, resource, context_json, entities_json)?;
+
+        let allowed = response.decision() == Decision::Allow;
+        let reasons: Vec<String> = response.diagnostics().reason().map(|p| p.to_string()).collect();
+        let errors: Vec<String> = response.diagnostics().errors().map(|e| e.to_string()).collect();
+
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok((allowed, reasons, errors))
+    }
+
+    /// Like `is_authorized_detailed`, but each reason is a dict with the
+    /// determining policy's `id` and `effect` (`"permit"` or `"forbid"`).
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn is_authorized_with_effects(
+        &self,
+        py: Python<'_>,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<(bool, Vec<PyObject>, Vec<String>)> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let response =
+            self.evaluate(&policies, principal, action, resource, context_json, entities_json)?;
+
+        let allowed = response.decision() == Decision::Allow;
+        let reasons = response
+            .diagnostics()
+            .reason()
+            .map(|id| {
+                let effect = policies.policy(id).map(|p| p.effect().to_string());
+                json_to_py(py, &serde_json::json!({ "id": id.to_string(), "effect": effect }))
+            })
+            .collect();
+        let errors: Vec<String> = response.diagnostics().errors().map(|e| e.to_string()).collect();
+
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok((allowed, reasons, errors))
+    }
+
+    /// Authorize a request and get a structured `CedarResponse`.
+    ///
+    /// `request_id` is not interpreted; it is stored on the response so
+    /// decisions can be correlated with application logs. With `timed`, the
+    /// response's `eval_micros` holds the time spent evaluating policies.
+    ///
+    /// `attribute_overrides` maps entity uids to attribute dicts overlaid on
+    /// those entities for this evaluation only, for "what if" checks; an
+    /// entity not in `entities_json` is created with just the overrides.
+    ///
+    /// `schema` is handled as in `is_authorized`.
+    ///
+    /// `hierarchy` maps entity uids to lists of parent uids kept outside the
+    /// entity data, e.g. group memberships from a directory service; they are
+    /// added to the parents in `entities_json` for this evaluation.
+    ///
+    /// `derive_context_from` maps context keys to entity attribute paths such
+    /// as `"principal.role"`, copied into the context before evaluation so
+    /// policies can read `context.role`; a missing attribute leaves its key
+    /// unset. Overrides and `hierarchy` apply before the paths are resolved.
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None, request_id=None, timed=false, attribute_overrides=None, schema=None, hierarchy=None, derive_context_from=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn authorize(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+        request_id: Option<String>,
+        timed: bool,
+        attribute_overrides: Option<HashMap<String, Bound<'_, PyDict>>>,
+        schema: Option<SchemaArg<'_>>,
+        hierarchy: Option<HashMap<String, Vec<String>>>,
+        derive_context_from: Option<HashMap<String, String>>,
+    ) -> PyResult<CedarResponse> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let mut entities = match attribute_overrides {
+            Some(overrides) => {
+                let mut parsed = HashMap::new();
+                for (uid, attrs) in overrides {
+                    let uid = parse_entity_uid(&uid, "override uid")?;
+                    parsed.insert(uid, py_to_json(attrs.as_any())?);
+                }
+                overlay_attributes(entities_json, parsed)?
+            },
+            None => parse_entities(entities_json)?,
+        };
+        if let Some(hierarchy) = hierarchy {
+            let mut parsed = HashMap::new();
+            for (uid, parents) in hierarchy {
+                let parents = parents
+                    .iter()
+                    .map(|parent| parse_entity_uid(parent, "hierarchy parent"))
+                    .collect::<Result<Vec<_>, _>>()?;
+                parsed.insert(parse_entity_uid(&uid, "hierarchy uid")?, parents);
+            }
+            entities = overlay_parents(&entities, parsed)?;
+        }
+        // The allowlist screens what the caller sent, not the keys derived from entities
+        let allowed_context = self.allowed_context(context_json)?;
+        let supplied_context = allowed_context.as_deref().or(context_json);
+        let derived_context = match derive_context_from {
+            Some(derive) => {
+                let request = [
+                    ("principal", &parse_entity_uid(principal, "principal")?),
+                    ("action", &parse_entity_uid(action, "action")?),
+                    ("resource", &parse_entity_uid(resource, "resource")?),
+                ];
+                Some(derive_context(supplied_context, &derive, &entities, request)?)
+            },
+            None => None,
+        };
+        let schema = schema.as_ref().map(SchemaArg::resolve).transpose()?;
+        let prepared = self.prepare_allowed(
+            principal,
+            action,
+            resource,
+            derived_context.as_deref().or(supplied_context),
+            &entities,
+            schema.as_deref(),
+        )?;
+        let evaluation = self.run(&prepared, &policies, &entities);
+
+        self.report_request_if_slow(
+            called.elapsed(),
+            principal,
+            Some(action),
+            Some(resource),
+            context_json,
+            request_id.as_deref(),
+        );
+        Ok(CedarResponse::from_evaluation(&evaluation, request_id, timed))
+    }
+
+    /// Heuristic guidance on what to change so a denied request is allowed.
+    ///
+    /// Returns a dict whose `kind` is:
+    /// - `"allowed"` when nothing needs to change;
+    /// - `"relax_forbid"` when forbids block the request; `policies` lists
+    ///   them and `permits` the permits that would allow it without them;
+    /// - `"missing_permit"` when no permit applies; `suggestion` is a minimal
+    ///   permit for exactly this request.
+    ///
+    /// `message` summarizes the advice, and `errors` lists evaluation errors,
+    /// which can also explain why a permit didn't apply.
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn suggest_fix(
+        &self,
+        py: Python<'_>,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let entities = parse_entities(entities_json)?;
+        let decide = |policies: &PolicySet| {
+            self.evaluate_parsed(policies, principal, action, resource, context_json, &entities, None)
+        };
+        let response = decide(&policies)?;
+        let errors: Vec<String> = response.diagnostics().errors().map(|e| e.to_string()).collect();
+        let sorted_reasons = |response: &Response| {
+            let mut ids: Vec<String> = response.diagnostics().reason().map(|id| id.to_string()).collect();
+            ids.sort();
+            ids
+        };
+
+        let mut advice = if response.decision() == Decision::Allow {
+            serde_json::json!({ "kind": "allowed", "message": "The request is already allowed." })
+        } else if response.diagnostics().reason().next().is_some() {
+            let forbids = sorted_reasons(&response);
+            let without_forbids = decide(&policies_with_effect(&policies, Effect::Permit))?;
+            let quoted: Vec<String> = forbids.iter().map(|id| format!("'{}'", id)).collect();
+            serde_json::json!({
+                "kind": "relax_forbid",
+                "policies": forbids,
+                "permits": sorted_reasons(&without_forbids),
+                "message": format!("Relax or remove forbid {} to allow this request.", quoted.join(", ")),
+            })
+        } else {
+            let principal_uid = parse_entity_uid(principal, "principal")?;
+            let action_uid = parse_entity_uid(action, "action")?;
+            let resource_uid = parse_entity_uid(resource, "resource")?;
+            serde_json::json!({
+                "kind": "missing_permit",
+                "suggestion": format!(
+                    "permit(principal == {}, action == {}, resource == {});",
+                    principal_uid, action_uid, resource_uid
+                ),
+                "message": "No permit applies to this request; add one, or widen an existing permit.",
+            })
+        };
+
+        advice["errors"] = serde_json::json!(errors);
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok(json_to_py(py, &advice))
+    }
+
+    /// Render a request's evaluation as a multi-line, human-readable trace.
+    ///
+    /// The trace lists the request, the decision, each determining policy
+    /// with its Cedar source, and any evaluation errors. Intended for
+    /// debugging; the format is not stable and shouldn't be parsed.
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
+    fn trace(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<String> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let entities = parse_entities(entities_json)?;
+        let evaluation = self.evaluate_full(
+            &policies,
+            principal,
+            action,
+            resource,
+            context_json,
+            &entities,
+            None,
+        )?;
+        let response = &evaluation.response;
+
+        let mut lines = vec![
+            "Request:".to_string(),
+            format!("  principal: {}", principal),
+            format!("  action:    {}", action),
+            format!("  resource:  {}", resource),
+            format!("  context:   {}", context_json.unwrap_or("{}")),
+            format!("Decision: {:?}", response.decision()),
+            format!("Evaluated conditions: {}", evaluation.evaluated_conditions),
+        ];
+
+        let mut reasons: Vec<_> = response.diagnostics().reason().collect();
+        reasons.sort();
+        if reasons.is_empty() {
+            lines.push("Determining policies: (none)".to_string());
+        } else {
+            lines.push("Determining policies:".to_string());
+            for id in reasons {
+                lines.push(format!("  - {}", id));
+                if let Some(policy) = policies.policy(id) {
+                    lines.extend(policy.to_string().lines().map(|line| format!("      {}", line)));
+                }
+            }
+        }
+
+        let errors: Vec<String> = response.diagnostics().errors().map(|e| e.to_string()).collect();
+        if errors.is_empty() {
+            lines.push("Errors: (none)".to_string());
+        } else {
+            lines.push("Errors:".to_string());
+            lines.extend(errors.iter().map(|e| format!("  - {}", e)));
+        }
+
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok(lines.join("\n"))
+    }
+
+    /// Decide one request under each of several candidate contexts.
+    ///
+    /// Returns `"Allow"` or `"Deny"` per context, in order. The entities are
+    /// parsed once and shared by every evaluation.
+    #[pyo3(signature = (policy_set, principal, action, resource, contexts, entities_json=None))]
+    fn authorize_context_matrix(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        contexts: Vec<Bound<'_, PyDict>>,
+        entities_json: Option<&str>,
+    ) -> PyResult<Vec<String>> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let entities = parse_entities(entities_json)?;
+        let mut decisions = Vec::with_capacity(contexts.len());
+        for context in contexts {
+            let context_json = py_to_json(context.as_any())?.to_string();
+            let response = self.evaluate_parsed(
+                &policies,
+                principal,
+                action,
+                resource,
+                Some(&context_json),
+                &entities,
+                None,
+            )?;
+            let allowed = response.decision() == Decision::Allow;
+            decisions.push(if allowed { "Allow" } else { "Deny" }.to_string());
+        }
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), None);
+        Ok(decisions)
+    }
+
+    /// Authorize against a shared `CedarEntities` store plus per-request entities.
+    ///
+    /// `extra_entities` (a JSON string or a list of entity dicts) is merged
+    /// into a copy of `entities` for this call only; the shared store is not
+    /// modified.
+    #[pyo3(signature = (policy_set, principal, action, resource, entities, context_json=None, extra_entities=None, request_id=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn authorize_with_store(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        entities: &CedarEntities,
+        context_json: Option<&str>,
+        extra_entities: Option<&Bound<'_, PyAny>>,
+        request_id: Option<String>,
+    ) -> PyResult<CedarResponse> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let merged = match extra_entities {
+            Some(extra) => {
+                let extra = match extra.downcast::<PyString>() {
+                    Ok(json_str) => {
+                        let json_str = json_str.to_str()?;
+                        check_input_size("Entities", json_str.as_bytes())?;
+                        parse_json(json_str, "Failed to parse entities JSON")?
+                    },
+                    Err(_) => py_to_json(extra)?,
+                };
+                Some(guard_parse("entities", || {
+                    entities.entities.clone().add_entities_from_json_value(extra, None).map_err(|e| {
+                        CedarError::JsonError(format!("Failed to merge extra entities: {}", e))
+                    })
+                })?)
+            },
+            None => None,
+        };
+        let evaluation = self.evaluate_full(
+            &policies,
+            principal,
+            action,
+            resource,
+            context_json,
+            merged.as_ref().unwrap_or(&entities.entities),
+            None,
+        )?;
+        self.report_request_if_slow(
+            called.elapsed(),
+            principal,
+            Some(action),
+            Some(resource),
+            context_json,
+            request_id.as_deref(),
+        );
+        Ok(CedarResponse::from_evaluation(&evaluation, request_id, false))
+    }
+
+    /// Authorize a request bundled with its entities in one JSON document.
+    ///
+    /// The bundle is an object with:
+    ///
+    /// - `principal`, `action`, `resource`: uid strings (`User::"alice"`) or
+    ///   `{"type": ..., "id": ...}` objects (required)
+    /// - `context`: a context record (optional, defaults to `{}`)
+    /// - `entities`: a list in Cedar's entities JSON format (optional)
+    fn authorize_bundle(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        bundle_json: &str,
+    ) -> PyResult<CedarResponse> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        check_input_size("Bundle", bundle_json.as_bytes())?;
+        let bundle = parse_json(bundle_json, "Invalid bundle JSON")?;
+        let entities = match bundle.get("entities") {
+            Some(entities) => guard_parse("entities", || {
+                Entities::from_json_value(entities.clone(), None).map_err(|e| {
+                    CedarError::JsonError(format!("Failed to parse entities JSON: {}", e))
+                })
+            })?,
+            None => Entities::empty(),
+        };
+        let context = bundle.get("context").map(|c| c.to_string());
+        let principal = request_uid(&bundle, "principal")?;
+        let action = request_uid(&bundle, "action")?;
+        let resource = request_uid(&bundle, "resource")?;
+
+        let evaluation = self.evaluate_full(
+            &policies,
+            &principal,
+            &action,
+            &resource,
+            context.as_deref(),
+            &entities,
+            None,
+        )?;
+        self.report_if_slow(
+            called.elapsed(),
+            &principal,
+            Some(&action),
+            Some(&resource),
+            context.as_deref(),
+        );
+        Ok(CedarResponse::from_evaluation(&evaluation, None, false))
+    }
+
+    /// Enumerate the `(action, resource)` pairs `principal` is allowed.
+    ///
+    /// Resources are the non-action entities in `entities_json`, optionally
+    /// narrowed to `resource_type`. Actions are `actions` when given, otherwise
+    /// the action entities in the store plus every action named in a policy
+    /// scope. Each pair is a full authorization, so filters bound the cost.
+    #[pyo3(signature = (policy_set, principal, entities_json, actions=None, resource_type=None))]
+    fn effective_permissions(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        entities_json: &str,
+        actions: Option<Vec<String>>,
+        resource_type: Option<&str>,
+    ) -> PyResult<Vec<(String, String)>> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let entities = parse_entities(Some(entities_json))?;
+        let is_action = |uid: &EntityUid| {
+            let ty = uid.type_name().to_string();
+            ty == "Action" || ty.ends_with("::Action")
+        };
+
+        let actions: Vec<String> = match actions {
+            Some(actions) => actions,
+            None => {
+                let mut found: Vec<EntityUid> =
+                    entities.iter().map(|e| e.uid()).filter(|uid| is_action(uid)).collect();
+                for policy in policies.policies() {
+                    match policy.action_constraint() {
+                        ActionConstraint::Any => {},
+                        ActionConstraint::Eq(uid) => found.push(uid),
+                        ActionConstraint::In(uids) => found.extend(uids),
+                    }
+                }
+                let mut names: Vec<String> = found.iter().map(|u| u.to_string()).collect();
+                names.sort();
+                names.dedup();
+                names
+            },
+        };
+        let mut resources: Vec<String> = entities
+            .iter()
+            .map(|e| e.uid())
+            .filter(|uid| !is_action(uid))
+            .filter(|uid| resource_type.is_none_or(|ty| uid.type_name().to_string() == ty))
+            .map(|uid| uid.to_string())
+            .collect();
+        resources.sort();
+
+        let mut allowed = Vec::new();
+        for action in &actions {
+            for resource in &resources {
+                let response = self.evaluate_parsed(
+                    &policies,
+                    principal,
+                    action,
+                    resource,
+                    None,
+                    &entities,
+                    None,
+                )?;
+                if response.decision() == Decision::Allow {
+                    allowed.push((action.clone(), resource.clone()));
+                }
+            }
+        }
+        self.report_if_slow(called.elapsed(), principal, None, None, None);
+        Ok(allowed)
+    }
+
+    /// Authorize a request and raise unless the decision is `expected`.
+    ///
+    /// `expected` is `"allow"` or `"deny"` (case-insensitive). On a match the
+    /// `CedarResponse` is returned; on a mismatch `CedarAuthorizationError` is
+    /// raised naming the actual decision, determining policies, and errors.
+    #[pyo3(signature = (expected, policy_set, principal, action, resource, context_json=None, entities_json=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn assert_decision(
+        &self,
+        expected: &str,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<CedarResponse> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let expect_allow = match expected.to_ascii_lowercase().as_str() {
+            "allow" => true,
+            "deny" => false,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Expected decision must be 'allow' or 'deny', got '{}'",
+                    expected
+                )))
+            }
+        };
+        let entities = parse_entities(entities_json)?;
+        let evaluation = self.evaluate_full(
+            &policies,
+            principal,
+            action,
+            resource,
+            context_json,
+            &entities,
+            None,
+        )?;
+        let result = CedarResponse::from_evaluation(&evaluation, None, false);
+        // Report before checking, so a slow failing assertion is still reported
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+
+        if result.allowed != expect_allow {
+            return Err(CedarError::AuthorizationError(format!(
+                "expected {} but got {} for {} -> {} -> {}; determining policies: {:?}; errors: {:?}",
+                if expect_allow { "Allow" } else { "Deny" },
+                result.decision,
+                principal,
+                action,
+                resource,
+                result.reasons,
+                result.errors
+            ))
+            .into());
+        }
+        Ok(result)
+    }
+
+    /// Evaluate a request against each policy on its own.
+    ///
+    /// Maps every policy id to `"permit"` or `"forbid"` when the policy is
+    /// satisfied, `"nomatch"` when it isn't, and `"error"` when evaluating it
+    /// failed.
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
+    fn evaluate_each(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<BTreeMap<String, String>> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let entities = parse_entities(entities_json)?;
+        let mut outcomes = BTreeMap::new();
+        for policy in policies.policies() {
+            let single = single_policy_set(&policies, policy)?;
+            let response = self.evaluate_parsed(
+                &single,
+                principal,
+                action,
+                resource,
+                context_json,
+                &entities,
+                None,
+            )?;
+            let outcome = if response.diagnostics().errors().next().is_some() {
+                "error".to_string()
+            } else if response.diagnostics().reason().next().is_some() {
+                policy.effect().to_string()
+            } else {
+                "nomatch".to_string()
+            };
+            outcomes.insert(policy.id().to_string(), outcome);
+        }
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok(outcomes)
+    }
+
+    /// Report whether a permit and a forbid both apply to a concrete request.
+    ///
+    /// Returns `(conflict, permit_ids, forbid_ids)` with the ids of the satisfied
+    /// policies of each effect. Cedar still decides such a request as `Deny`;
+    /// this only surfaces the overlap.
+    #[pyo3(signature = (policy_set, principal, action, resource, context_json=None, entities_json=None))]
+    fn conflicts(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<(bool, Vec<String>, Vec<String>)> {
+        let called = Instant::now();
+        let policies = policy_set.borrow().snapshot();
+        let entities = parse_entities(entities_json)?;
+        let matching = |effect| -> PyResult<Vec<String>> {
+            let response = self.evaluate_parsed(
+                &policies_with_effect(&policies, effect),
+                principal,
+                action,
+                resource,
+                context_json,
+                &entities,
+                None,
+            )?;
+            let mut ids: Vec<String> = response.diagnostics().reason().map(|p| p.to_string()).collect();
+            ids.sort();
+            Ok(ids)
+        };
+        let permits = matching(Effect::Permit)?;
+        let forbids = matching(Effect::Forbid)?;
+
+        self.report_if_slow(called.elapsed(), principal, Some(action), Some(resource), context_json);
+        Ok((!permits.is_empty() && !forbids.is_empty(), permits, forbids))
+    }
 
-        let response = self.authorizer.is_authorized(&request, &policy_set.policies, &entities);
+    /// Return the sorted ids of policies whose scope matches a request, ignoring conditions.
+    ///
+    /// `in` constraints are resolved against the optional entities hierarchy; without
+    /// it only direct equality satisfies them.
+    #[pyo3(signature = (policy_set, principal, action, resource, entities_json=None))]
+    fn matching_scopes(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        entities_json: Option<&str>,
+    ) -> PyResult<Vec<String>> {
+        let policies = policy_set.borrow().snapshot();
+        let principal_uid = parse_entity_uid(principal, "principal")?;
+        let action_uid = parse_entity_uid(action, "action")?;
+        let resource_uid = parse_entity_uid(resource, "resource")?;
+        let entities = parse_entities(entities_json)?;
 
-        let allowed = response.decision() == Decision::Allow;
-        let reasons: Vec<String> = response.diagnostics().reason().map(|p| p.to_string()).collect();
-        let errors: Vec<String> = response.diagnostics().errors().map(|e| e.to_string()).collect();
+        let mut ids: Vec<String> = policies
+            .policies()
+            .filter(|p| {
+                scope_matches(p, Some(&principal_uid), &action_uid, Some(&resource_uid), &entities)
+            })
+            .map(|p| p.id().to_string())
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
 
-        Ok((allowed, reasons, errors))
+    /// Report whether some permit could apply when principal and/or resource are unconstrained.
+    ///
+    /// Omitted components are treated as unknowns and the request is partially
+    /// evaluated, so this answers "could anyone be allowed?" rather than deciding
+    /// a concrete request.
+    #[pyo3(signature = (policy_set, action, principal=None, resource=None, context_json=None, entities_json=None))]
+    fn is_permit_reachable(
+        &self,
+        policy_set: &Bound<'_, CedarPolicySet>,
+        action: &str,
+        principal: Option<&str>,
+        resource: Option<&str>,
+        context_json: Option<&str>,
+        entities_json: Option<&str>,
+    ) -> PyResult<bool> {
+        let policies = policy_set.borrow().snapshot();
+        let action_uid = parse_entity_uid(action, "action")?;
+        let principal_uid = principal.map(|p| parse_entity_uid(p, "principal")).transpose()?;
+        let resource_uid = resource.map(|r| parse_entity_uid(r, "resource")).transpose()?;
+        let entities = parse_entities(entities_json)?;
+        let allowed_context = self.allowed_context(context_json)?;
+
+        let mut builder = RequestBuilder::default()
+            .action(action_uid.clone())
+            .context(parse_context(allowed_context.as_deref().or(context_json), None)?);
+        if let Some(uid) = &principal_uid {
+            builder = builder.principal(uid.clone());
+        }
+        if let Some(uid) = &resource_uid {
+            builder = builder.resource(uid.clone());
+        }
+
+        let response = self
+            .authorizer
+            .is_authorized_partial(&builder.build(), &policies, &entities);
+
+        Ok(match response.decision() {
+            Some(decision) => decision == Decision::Allow,
+            // Undecided: a permit is reachable if one could still be determining. Residuals
+            // lose their scope and over-approximate, so re-check the original policy's scope
+            // against the known components.
+            None => response
+                .may_be_determining()
+                .filter(|residual| residual.effect() == Effect::Permit)
+                .filter_map(|residual| policies.policy(residual.id()))
+                .any(|p| {
+                    scope_matches(
+                        p,
+                        principal_uid.as_ref(),
+                        &action_uid,
+                        resource_uid.as_ref(),
+                        &entities,
+                    )
+                }),
+        })
+    }
+}
+
+/// Python wrapper for a Cedar EntityUid
+#[pyclass(name = "CedarEntityUid")]
+#[derive(Clone)]
+struct CedarEntityUid {
+    uid: EntityUid,
+}
+
+#[pymethods]
+impl CedarEntityUid {
+    /// Parse a uid such as `App::User::"alice"`
+    #[new]
+    fn new(uid_str: &str) -> PyResult<Self> {
+        Ok(CedarEntityUid {
+            uid: parse_entity_uid(uid_str, "entity uid")?,
+        })
+    }
+
+    /// The entity type, including any namespace (e.g. `App::User`)
+    fn type_name(&self) -> String {
+        self.uid.type_name().to_string()
+    }
+
+    /// The entity id, unquoted and unescaped (e.g. `alice`)
+    fn id(&self) -> &str {
+        self.uid.id().unescaped()
+    }
+
+    fn __str__(&self) -> String {
+        self.uid.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CedarEntityUid({})", self.uid)
+    }
+}
+
+/// Convert a Python attribute value to entity JSON, refusing values without one clear Cedar type.
+///
+/// `bool` is a Boolean, `int` a Long, `str` a String, `CedarEntityUid` an
+/// entity reference, `list`/`tuple`/`set` a Set, and a `dict` with string
+/// keys a Record. `path` names the value in errors, e.g. `attrs.tags[2]`.
+fn typed_attr_json(path: &str, value: &Bound<'_, PyAny>) -> Result<JsonValue, CedarError> {
+    let type_err = |message: String| CedarError::JsonError(format!("{}: {}", path, message));
+    if let Ok(b) = value.downcast::<PyBool>() {
+        Ok(JsonValue::Bool(b.is_true()))
+    } else if let Ok(i) = value.downcast::<PyInt>() {
+        let i: i64 = i.extract().map_err(|_| type_err(format!("{} is out of range for Long", i)))?;
+        Ok(JsonValue::from(i))
+    } else if let Ok(s) = value.downcast::<PyString>() {
+        Ok(JsonValue::String(s.to_string()))
+    } else if let Ok(uid) = value.extract::<PyRef<'_, CedarEntityUid>>() {
+        Ok(serde_json::json!({
+            "__entity": { "type": uid.uid.type_name().to_string(), "id": uid.uid.id().unescaped() },
+        }))
+    } else if let Ok(dict) = value.downcast::<PyDict>() {
+        let mut record = serde_json::Map::new();
+        for (k, v) in dict.iter() {
+            let key = k
+                .downcast::<PyString>()
+                .map_err(|_| type_err(format!("record keys must be strings, got {}", k)))?
+                .to_string();
+            if key.starts_with("__") {
+                // Cedar's entity JSON reserves `__entity`/`__extn` for escapes
+                return Err(type_err(format!(
+                    "record key '{}' is ambiguous; keys can't start with __",
+                    key
+                )));
+            }
+            let value = typed_attr_json(&format!("{}.{}", path, key), &v)?;
+            record.insert(key, value);
+        }
+        Ok(JsonValue::Object(record))
+    } else if value.is_instance_of::<PyList>()
+        || value.is_instance_of::<PyTuple>()
+        || value.is_instance_of::<PySet>()
+        || value.is_instance_of::<PyFrozenSet>()
+    {
+        let mut elements = Vec::new();
+        let iter_err = |e: PyErr| type_err(format!("failed to iterate: {}", e));
+        for (i, element) in value.iter().map_err(iter_err)?.enumerate() {
+            let element = element.map_err(iter_err)?;
+            elements.push(typed_attr_json(&format!("{}[{}]", path, i), &element)?);
+        }
+        Ok(JsonValue::Array(elements))
+    } else if value.downcast::<PyFloat>().is_ok() {
+        Err(type_err("float has no Cedar type; use an int or a decimal(\"...\") string".to_string()))
+    } else if value.is_none() {
+        Err(type_err("None has no Cedar type; leave the attribute out instead".to_string()))
+    } else {
+        Err(type_err(format!("{} has no Cedar type", value.get_type())))
+    }
+}
+
+/// Builds a `CedarEntities` store from Python values with explicit Cedar types
+#[pyclass(name = "CedarEntityBuilder")]
+#[derive(Default)]
+struct CedarEntityBuilder {
+    records: Vec<JsonValue>,
+}
+
+#[pymethods]
+impl CedarEntityBuilder {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entity with attributes converted by strict type rules.
+    ///
+    /// `bool` becomes a Boolean, `int` a Long, `str` a String (never an
+    /// entity reference), `CedarEntityUid` an entity reference, a list, tuple,
+    /// or set a Set, and a dict a Record. Anything else, including `float`
+    /// and `None`, raises `CedarJsonError` naming the attribute, as do
+    /// integers outside the Long range. `parents` are entity uid strings.
+    #[pyo3(signature = (uid, attrs=None, parents=None))]
+    fn add(
+        &mut self,
+        uid: &str,
+        attrs: Option<&Bound<'_, PyDict>>,
+        parents: Option<Vec<String>>,
+    ) -> PyResult<()> {
+        let uid_json = |uid: &EntityUid| {
+            serde_json::json!({ "type": uid.type_name().to_string(), "id": uid.id().unescaped() })
+        };
+        let uid = parse_entity_uid(uid, "entity uid")?;
+        let attrs = match attrs {
+            Some(attrs) => typed_attr_json("attrs", attrs.as_any())?,
+            None => JsonValue::Object(serde_json::Map::new()),
+        };
+        let parents = parents
+            .unwrap_or_default()
+            .iter()
+            .map(|parent| parse_entity_uid(parent, "parent").map(|p| uid_json(&p)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.records
+            .push(serde_json::json!({ "uid": uid_json(&uid), "attrs": attrs, "parents": parents }));
+        Ok(())
+    }
+
+    /// Build the store, checking it against `schema` as `CedarEntities` does when one is given
+    #[pyo3(signature = (schema=None, lenient_entities=false))]
+    fn build(&self, schema: Option<&CedarSchema>, lenient_entities: bool) -> PyResult<CedarEntities> {
+        let records = JsonValue::Array(self.records.clone()).to_string();
+        CedarEntities::new(&records, schema, lenient_entities)
+    }
+
+    fn __len__(&self) -> usize {
+        self.records.len()
+    }
+}
+
+/// Leading bytes identifying a `CedarEntities.to_bytes` blob and its format version
+const ENTITIES_BLOB_HEADER: &[u8] = b"CEDARPY-ENTITIES\x01";
+
+/// Top-level keys of an entity record that Cedar itself reads
+const ENTITY_RECORD_KEYS: &[&str] = &["uid", "attrs", "parents", "tags"];
+
+/// Extra top-level keys (e.g. a `__source` marker) on each entity record, by uid.
+///
+/// Cedar ignores such keys, so they are kept alongside the store instead.
+fn entity_metadata(document: &JsonValue) -> HashMap<EntityUid, serde_json::Map<String, JsonValue>> {
+    let mut metadata = HashMap::new();
+    for record in document.as_array().into_iter().flatten().filter_map(JsonValue::as_object) {
+        let extra: serde_json::Map<String, JsonValue> = record
+            .iter()
+            .filter(|(key, _)| !ENTITY_RECORD_KEYS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if extra.is_empty() {
+            continue;
+        }
+        if let Some(uid) = record.get("uid").and_then(|u| EntityUid::from_json(u.clone()).ok()) {
+            metadata.insert(uid, extra);
+        }
+    }
+    metadata
+}
+
+/// Rebuild every object in `value` with its keys in sorted order, so that
+/// serialization doesn't depend on the order they were parsed in
+fn sort_json_keys(value: &mut JsonValue) {
+    match value {
+        JsonValue::Object(fields) => {
+            let mut sorted: Vec<(String, JsonValue)> = std::mem::take(fields).into_iter().collect();
+            sorted.sort_by(|a, b| a.0.cmp(&b.0));
+            for (key, mut field) in sorted {
+                sort_json_keys(&mut field);
+                fields.insert(key, field);
+            }
+        },
+        JsonValue::Array(items) => items.iter_mut().for_each(sort_json_keys),
+        _ => {},
+    }
+}
+
+/// Build an entity store from entities JSON, checking it against `schema` if one is given.
+///
+/// `lenient_entities` is as for `CedarEntities.__new__`.
+fn entities_from_json(
+    mut document: JsonValue,
+    schema: Option<&CedarSchema>,
+    lenient_entities: bool,
+) -> Result<Entities, CedarError> {
+    let parse_err = |e: cedar_policy::entities_errors::EntitiesError| {
+        CedarError::JsonError(format!("Failed to parse entities JSON: {}", e))
+    };
+    let Some(schema) = schema else {
+        return Entities::from_json_value(document, None).map_err(parse_err);
+    };
+
+    apply_schema_defaults(&mut document, schema)?;
+    if lenient_entities {
+        let (declared, extra) = split_undeclared(document, schema);
+        Entities::from_json_value(declared, Some(&schema.schema))
+            .map_err(parse_err)?
+            .add_entities_from_json_value(extra, None)
+            .map_err(parse_err)
+    } else {
+        Entities::from_json_value(document, Some(&schema.schema)).map_err(parse_err)
+    }
+}
+
+/// Python wrapper for a parsed Cedar entity store
+#[pyclass(name = "CedarEntities")]
+#[derive(Clone)]
+struct CedarEntities {
+    entities: Entities,
+    /// Extra top-level keys from each entity's JSON record, ignored by evaluation
+    metadata: HashMap<EntityUid, serde_json::Map<String, JsonValue>>,
+}
+
+impl CedarEntities {
+    /// The store as Cedar's entity JSON, with each record's extra keys restored
+    fn to_json_document(&self) -> Result<JsonValue, CedarError> {
+        let mut document = self
+            .entities
+            .to_json_value()
+            .map_err(|e| CedarError::JsonError(format!("Failed to serialize entities: {}", e)))?;
+        for record in document.as_array_mut().into_iter().flatten() {
+            let uid = record.get("uid").and_then(|u| EntityUid::from_json(u.clone()).ok());
+            let Some(extra) = uid.and_then(|uid| self.metadata.get(&uid)) else {
+                continue;
+            };
+            if let Some(fields) = record.as_object_mut() {
+                fields.extend(extra.clone());
+            }
+        }
+        Ok(document)
+    }
+}
+
+#[pymethods]
+impl CedarEntities {
+    /// Parse entities JSON once so it can be reused across requests.
+    ///
+    /// With a schema, attribute values are interpreted and checked against the
+    /// declared entity types, and attributes the schema annotates with
+    /// `@default("...")` are filled in when an entity omits them.
+    /// `lenient_entities` drops attributes the schema doesn't declare and
+    /// accepts entities of undeclared types without checking them, while
+    /// declared types and attributes are still validated.
+    #[new]
+    #[pyo3(signature = (entities_json, schema=None, lenient_entities=false))]
+    fn new(
+        entities_json: &str,
+        schema: Option<&CedarSchema>,
+        lenient_entities: bool,
+    ) -> PyResult<Self> {
+        check_input_size("Entities", entities_json.as_bytes())?;
+        guard_parse("entities", || {
+            let document = parse_json(entities_json, "Failed to parse entities JSON")?;
+            let metadata = entity_metadata(&document);
+            let entities = entities_from_json(document, schema, lenient_entities)?;
+            Ok(CedarEntities { entities, metadata })
+        })
+    }
+
+    /// Serialize the store for later `from_bytes`.
+    ///
+    /// The payload is a version header followed by Cedar's JSON entity format,
+    /// so blobs stay readable across releases.
+    fn to_bytes<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let json = self.to_json_document()?;
+        let mut data = ENTITIES_BLOB_HEADER.to_vec();
+        data.extend(serde_json::to_vec(&json).map_err(|e| {
+            CedarError::JsonError(format!("Failed to serialize entities: {}", e))
+        })?);
+        Ok(PyBytes::new_bound(py, &data))
+    }
+
+    /// Reload a store written by `to_bytes`, checking it as the constructor does
+    #[staticmethod]
+    #[pyo3(signature = (data, schema=None, lenient_entities=false))]
+    fn from_bytes(data: &[u8], schema: Option<&CedarSchema>, lenient_entities: bool) -> PyResult<Self> {
+        check_input_size("Entities", data)?;
+        let payload = data.strip_prefix(ENTITIES_BLOB_HEADER).ok_or_else(|| {
+            CedarError::JsonError("Not a CedarEntities blob: missing header".to_string())
+        })?;
+        let payload = std::str::from_utf8(payload).map_err(|e| {
+            CedarError::JsonError(format!("Corrupt CedarEntities blob: invalid UTF-8: {}", e))
+        })?;
+        guard_parse("entities", || {
+            let json: JsonValue = serde_json::from_str(payload)
+                .map_err(|e| CedarError::JsonError(format!("Corrupt CedarEntities blob: {}", e)))?;
+            let metadata = entity_metadata(&json);
+            let entities = entities_from_json(json, schema, lenient_entities)?;
+            Ok(CedarEntities { entities, metadata })
+        })
+    }
+
+    /// The JSON record for `uid` as a dict, or `None` if the store lacks it.
+    ///
+    /// Extra top-level keys given in the input, such as a `__source` marker,
+    /// are included even though evaluation ignores them.
+    fn get(&self, py: Python<'_>, uid: &str) -> PyResult<Option<PyObject>> {
+        let uid = parse_entity_uid(uid, "uid")?;
+        let Some(entity) = self.entities.get(&uid) else {
+            return Ok(None);
+        };
+        let mut record = entity
+            .to_json_value()
+            .map_err(|e| CedarError::JsonError(format!("Failed to serialize entity: {}", e)))?;
+        if let (Some(extra), Some(fields)) = (self.metadata.get(&uid), record.as_object_mut()) {
+            fields.extend(extra.clone());
+        }
+        Ok(Some(json_to_py(py, &record)))
+    }
+
+    fn __len__(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Compare with `other`, reporting how to get from this store to it.
+    ///
+    /// Returns `{"added": [...], "removed": [...], "changed": {...}}`. `added`
+    /// and `removed` list uids present only in `other` or only in `self`;
+    /// `changed` maps uids present in both to `{"attributes": [...],
+    /// "parents": bool}`, naming the attributes whose values differ and whether
+    /// the ancestors differ. All lists are sorted.
+    fn diff(&self, py: Python<'_>, other: &CedarEntities) -> PyObject {
+        let attrs = |entity: &cedar_policy::Entity| {
+            let attrs: BTreeMap<String, Option<cedar_policy::EvalResult>> =
+                entity.attrs().map(|(name, value)| (name.to_string(), value.ok())).collect();
+            attrs
+        };
+        let ancestors = |store: &Entities, uid: &EntityUid| -> Vec<String> {
+            let mut ancestors: Vec<String> = store
+                .ancestors(uid)
+                .map(|a| a.map(|a| a.to_string()).collect())
+                .unwrap_or_default();
+            ancestors.sort();
+            ancestors
+        };
+
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut changed = BTreeMap::new();
+        for entity in self.entities.iter() {
+            let uid = entity.uid();
+            let Some(theirs) = other.entities.get(&uid) else {
+                removed.push(uid.to_string());
+                continue;
+            };
+            let (mine, theirs) = (attrs(entity), attrs(theirs));
+            let attributes: Vec<&String> = mine
+                .keys()
+                .chain(theirs.keys().filter(|name| !mine.contains_key(*name)))
+                .filter(|name| mine.get(*name) != theirs.get(*name))
+                .collect();
+            let parents = ancestors(&self.entities, &uid) != ancestors(&other.entities, &uid);
+            if !attributes.is_empty() || parents {
+                let mut attributes = attributes;
+                attributes.sort();
+                changed.insert(
+                    uid.to_string(),
+                    serde_json::json!({ "attributes": attributes, "parents": parents }),
+                );
+            }
+        }
+        for entity in other.entities.iter() {
+            if self.entities.get(&entity.uid()).is_none() {
+                added.push(entity.uid().to_string());
+            }
+        }
+        added.sort();
+        removed.sort();
+
+        json_to_py(py, &serde_json::json!({ "added": added, "removed": removed, "changed": changed }))
+    }
+
+    /// Number of entities of each type, keyed by type name
+    fn type_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for entity in self.entities.iter() {
+            *counts.entry(entity.uid().type_name().to_string()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// A GraphViz DOT digraph of the entities, with an edge from each entity to each parent.
+    ///
+    /// Nodes are labelled with entity uids; parents missing from the store
+    /// still appear as nodes. Memberships implied through another parent are
+    /// left out, so a user in a team inside an org has an edge to the team
+    /// only. Nodes and edges are sorted for stable output.
+    fn to_dot(&self) -> PyResult<String> {
+        let quote = |uid: &str| format!("\"{}\"", uid.replace('\\', "\\\\").replace('"', "\\\""));
+        let document = self.to_json_document()?;
+        let mut nodes = BTreeSet::new();
+        let mut edges = BTreeSet::new();
+        for record in document.as_array().into_iter().flatten() {
+            let Ok(uid) = EntityUid::from_json(record["uid"].clone()) else { continue };
+            nodes.insert(uid.to_string());
+            let parents: Vec<EntityUid> = record["parents"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|parent| EntityUid::from_json(parent.clone()).ok())
+                .collect();
+            // Cedar stores the transitive closure, so skip parents reachable through another one
+            for parent in &parents {
+                let inherited = parents
+                    .iter()
+                    .any(|other| other != parent && uid_in(&self.entities, other, parent));
+                nodes.insert(parent.to_string());
+                if !inherited {
+                    edges.insert((uid.to_string(), parent.to_string()));
+                }
+            }
+        }
+
+        let mut dot = String::from("digraph entities {\n");
+        for node in &nodes {
+            dot.push_str(&format!("    {};\n", quote(node)));
+        }
+        for (child, parent) in &edges {
+            dot.push_str(&format!("    {} -> {};\n", quote(child), quote(parent)));
+        }
+        dot.push_str("}\n");
+        Ok(dot)
+    }
+
+    /// Stable SHA3-256 hex digest of the store's contents, for use as a cache key.
+    ///
+    /// Each entity is hashed in normalized JSON form (sorted keys and parents),
+    /// in uid order, so key order, whitespace, and entity order in the source
+    /// don't matter. Extra record keys such as `__source` are not included.
+    fn content_hash(&self) -> PyResult<String> {
+        let mut entries = Vec::new();
+        for entity in self.entities.iter() {
+            let mut record = entity.to_json_value().map_err(|e| {
+                CedarError::JsonError(format!("Failed to serialize entity {}: {}", entity.uid(), e))
+            })?;
+            sort_json_keys(&mut record);
+            if let Some(parents) = record.get_mut("parents").and_then(JsonValue::as_array_mut) {
+                parents.sort_by_key(|p| p.to_string());
+            }
+            entries.push(record.to_string());
+        }
+        entries.sort();
+
+        let mut hasher = Sha3_256::new();
+        for entry in &entries {
+            hasher.update(entry.as_bytes());
+            hasher.update(b"\n");
+        }
+        Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
     }
 }
 
+/// Policies, templates, a schema, and an entity store behind one `authorize` call
+#[pyclass(name = "PolicyStore")]
+struct PolicyStore {
+    policy_set: CedarPolicySet,
+    // Shared so `authorize` can release its borrow of the store before evaluating
+    schema: Option<Arc<CedarSchema>>,
+    entities: Arc<Entities>,
+    authorizer: Arc<CedarAuthorizer>,
+}
+
+#[pymethods]
+impl PolicyStore {
+    /// Compose a store from existing pieces; every argument is optional.
+    ///
+    /// The policy set is copied, so later changes to it don't affect the store.
+    #[new]
+    #[pyo3(signature = (policy_set=None, schema=None, entities=None))]
+    fn new(
+        policy_set: Option<&CedarPolicySet>,
+        schema: Option<&CedarSchema>,
+        entities: Option<&CedarEntities>,
+    ) -> Self {
+        PolicyStore {
+            policy_set: policy_set.cloned().unwrap_or_else(CedarPolicySet::new),
+            schema: schema.cloned().map(Arc::new),
+            entities: Arc::new(entities.map(|e| e.entities.clone()).unwrap_or_else(Entities::empty)),
+            authorizer: Arc::new(CedarAuthorizer::internal()),
+        }
+    }
+
+    /// Load policies (Cedar syntax), a schema, and entities JSON from files.
+    ///
+    /// The policies are validated against the schema and the entities are
+    /// checked against it, tolerating undeclared data with `lenient_entities`
+    /// as `CedarEntities` does. Any failure is raised with the path of the
+    /// file it came from.
+    #[staticmethod]
+    #[pyo3(signature = (policies_path, schema_path, entities_path, lenient_entities=false))]
+    fn from_files(
+        py: Python<'_>,
+        policies_path: &str,
+        schema_path: &str,
+        entities_path: &str,
+        lenient_entities: bool,
+    ) -> PyResult<Self> {
+        let read = |path: &str| {
+            std::fs::read_to_string(path)
+                .map_err(|e| PyIOError::new_err(format!("Failed to read {}: {}", path, e)))
+        };
+        // Re-raise with the same exception type, prefixed by the offending file
+        let in_file = |path: &str, err: PyErr| {
+            let message = format!("{}: {}", path, err.value_bound(py));
+            PyErr::from_type_bound(err.get_type_bound(py), message)
+        };
+
+        let schema = CedarSchema::new(&read(schema_path)?).map_err(|e| in_file(schema_path, e))?;
+        let policy_set = CedarPolicySet::from_string(&read(policies_path)?)
+            .map_err(|e| in_file(policies_path, e))?;
+        let entities = CedarEntities::new(&read(entities_path)?, Some(&schema), lenient_entities)
+            .map_err(|e| in_file(entities_path, e))?;
+
+        let validator = Validator::new(schema.schema.clone());
+        let result = validator.validate(&policy_set.policies, ValidationMode::Strict);
+        let errors: Vec<String> = result
+            .validation_errors()
+            .map(|e| format!("{}: {}", e.policy_id(), e))
+            .collect();
+        if !errors.is_empty() {
+            return Err(CedarError::SchemaError(format!(
+                "{}: policies failed validation: {}",
+                policies_path,
+                errors.join("; ")
+            ))
+            .into());
+        }
+
+        Ok(PolicyStore::new(Some(&policy_set), Some(&schema), Some(&entities)))
+    }
+
+    fn add_policy(&mut self, policy: &CedarPolicy) -> PyResult<()> {
+        self.policy_set.add(policy, "error").map(|_| ())
+    }
+
+    fn add_template(&mut self, template: &CedarTemplate) -> PyResult<()> {
+        self.policy_set.add_template(template)
+    }
+
+    fn link(
+        &mut self,
+        template_id: &str,
+        link_id: &str,
+        values: HashMap<String, String>,
+    ) -> PyResult<()> {
+        self.policy_set.link(template_id, link_id, values)
+    }
+
+    fn set_schema(&mut self, schema: &CedarSchema) {
+        self.schema = Some(Arc::new(schema.clone()));
+    }
+
+    fn set_entities(&mut self, entities: &CedarEntities) {
+        self.entities = Arc::new(entities.entities.clone());
+    }
+
+    /// Authorize a request against the store's policies and entities.
+    ///
+    /// When a schema is set, the request and context are validated against it.
+    /// `timed` fills in the response's `eval_micros`. The store can be
+    /// changed from another thread while a request is evaluated; the request
+    /// sees the store as it was when the call began.
+    #[pyo3(signature = (principal, action, resource, context_json=None, request_id=None, timed=false))]
+    fn authorize(
+        slf: &Bound<'_, Self>,
+        principal: &str,
+        action: &str,
+        resource: &str,
+        context_json: Option<&str>,
+        request_id: Option<String>,
+        timed: bool,
+    ) -> PyResult<CedarResponse> {
+        let (policies, schema, entities, authorizer) = {
+            let store = slf.borrow();
+            (
+                store.policy_set.snapshot(),
+                store.schema.clone(),
+                Arc::clone(&store.entities),
+                Arc::clone(&store.authorizer),
+            )
+        };
+        let evaluation = authorizer.evaluate_full(
+            &policies,
+            principal,
+            action,
+            resource,
+            context_json,
+            &entities,
+            schema.as_deref(),
+        )?;
+        Ok(CedarResponse::from_evaluation(&evaluation, request_id, timed))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PolicyStore({} policies, {} entities, schema={})",
+            self.policy_set.__len__(),
+            self.entities.len(),
+            self.schema.is_some()
+        )
+    }
+}
+
+/// Deep-merge context records, with later entries taking precedence.
+///
+/// Nested records are merged key by key; any other conflicting value is
+/// replaced by the later one.
+#[pyfunction]
+fn merge_contexts(py: Python<'_>, contexts: Vec<Bound<'_, PyDict>>) -> PyResult<PyObject> {
+    let mut merged = JsonValue::Object(serde_json::Map::new());
+    for context in contexts {
+        deep_merge(&mut merged, py_to_json(context.as_any())?);
+    }
+    Ok(json_to_py(py, &merged))
+}
+
+/// A request component from request JSON, given as a uid string or `{"type", "id"}` object
+fn request_uid(request: &JsonValue, role: &str) -> Result<String, CedarError> {
+    match &request[role] {
+        JsonValue::String(uid) => Ok(uid.clone()),
+        JsonValue::Null => Err(CedarError::JsonError(format!("Request JSON is missing {}", role))),
+        other => EntityUid::from_json(other.clone())
+            .map(|uid| uid.to_string())
+            .map_err(|e| CedarError::JsonError(format!("Invalid {}: {}", role, e))),
+    }
+}
+
+/// Authorize a request given entirely as JSON, returning the decision as JSON.
+///
+/// `policies_json` is Cedar's policy set JSON (`staticPolicies`, `templates`,
+/// `templateLinks`). `request_json` has `principal`, `action`, and `resource`
+/// (uid strings or `{"type", "id"}` objects) and an optional `context` record.
+/// The result is `{"decision", "reasons", "errors"}`.
+#[pyfunction]
+fn authorize_json(policies_json: &str, request_json: &str, entities_json: &str) -> PyResult<String> {
+    check_input_size("Policy set", policies_json.as_bytes())?;
+    check_input_size("Request", request_json.as_bytes())?;
+    let policies = guard_parse("policies", || {
+        let policies = parse_json(policies_json, "Failed to parse policy set JSON")?;
+        PolicySet::from_json_value(policies)
+            .map_err(|e| CedarError::JsonError(format!("Failed to parse policy set JSON: {}", e)))
+    })?;
+    let request = parse_json(request_json, "Invalid request JSON")?;
+    let entities = parse_entities(Some(entities_json))?;
+
+    let context = request.get("context").map(|c| c.to_string());
+
+    let response = CedarAuthorizer::internal().evaluate_parsed(
+        &policies,
+        &request_uid(&request, "principal")?,
+        &request_uid(&request, "action")?,
+        &request_uid(&request, "resource")?,
+        context.as_deref(),
+        &entities,
+        None,
+    )?;
+    let result = CedarResponse::from_response(&response, None);
+    Ok(serde_json::json!({
+        "decision": result.decision,
+        "reasons": result.reasons,
+        "errors": result.errors,
+    })
+    .to_string())
+}
+
+/// Parse and validate policy sources up front, for fail-fast startup.
+///
+/// Returns a `PolicyStore` holding every policy that parsed, with `schema`
+/// set, and a list of problems: parse failures and duplicate ids (by source
+/// index) and schema validation errors (by policy id). An empty list means
+/// the store is ready to serve.
+#[pyfunction]
+fn build_validated(sources: Vec<String>, schema: &CedarSchema) -> (PolicyStore, Vec<String>) {
+    let mut policy_set = CedarPolicySet::new();
+    let mut problems = Vec::new();
+    for (index, source) in sources.iter().enumerate() {
+        let added = CedarPolicy::new(source, None, "auto").and_then(|p| policy_set.add(&p, "error"));
+        if let Err(e) = added {
+            problems.push(format!("source {}: {}", index, e));
+        }
+    }
+
+    let validator = Validator::new(schema.schema.clone());
+    let result = validator.validate(&policy_set.policies, ValidationMode::Strict);
+    problems.extend(
+        result
+            .validation_errors()
+            .map(|e| format!("{}: {}", e.policy_id(), e)),
+    );
+
+    (PolicyStore::new(Some(&policy_set), Some(schema), None), problems)
+}
+
+/// Turn a denied `CedarResponse` into a sentence suitable for end users.
+///
+/// Names the determining forbid policies, or explains that nothing permitted
+/// the request. Raises `ValueError` for an allowed response.
+#[pyfunction]
+fn explain_denial(response: &CedarResponse) -> PyResult<String> {
+    if response.allowed {
+        return Err(PyValueError::new_err("Response allowed the request; there is no denial to explain"));
+    }
+    let quoted: Vec<String> = response.reasons.iter().map(|id| format!("'{}'", id)).collect();
+    Ok(match quoted.as_slice() {
+        [] => "Access denied: no policy permits this request.".to_string(),
+        [only] => format!("Access denied: forbidden by policy {}.", only),
+        many => format!("Access denied: forbidden by policies {}.", many.join(", ")),
+    })
+}
+
+/// Split a possibly namespaced name into `(namespace, basename)`
+fn split_namespace(name: &str) -> (&str, &str) {
+    name.rsplit_once("::").unwrap_or(("", name))
+}
+
+/// Schema type of a sample JSON value, recording entity types it mentions.
+///
+/// Returns `None` when no type can be inferred, such as for an empty set, a
+/// null, or a non-integer number.
+fn infer_json_type(value: &JsonValue, mentioned: &mut BTreeSet<String>) -> Option<JsonValue> {
+    Some(match value {
+        JsonValue::Bool(_) => serde_json::json!({ "type": "Boolean" }),
+        JsonValue::Number(n) if n.is_i64() => serde_json::json!({ "type": "Long" }),
+        JsonValue::String(_) => serde_json::json!({ "type": "String" }),
+        JsonValue::Array(items) => {
+            let element = items.iter().find_map(|item| infer_json_type(item, mentioned))?;
+            serde_json::json!({ "type": "Set", "element": element })
+        },
+        JsonValue::Object(map) => {
+            if let Some(name) = map.get("__entity").and_then(|uid| uid["type"].as_str()) {
+                mentioned.insert(name.to_string());
+                return Some(serde_json::json!({ "type": "Entity", "name": name }));
+            }
+            if let Some(function) = map.get("__extn").and_then(|extn| extn["fn"].as_str()) {
+                let name = if function == "ip" { "ipaddr" } else { function };
+                return Some(serde_json::json!({ "type": "Extension", "name": name }));
+            }
+            let attributes: BTreeMap<&String, JsonValue> = map
+                .iter()
+                .filter_map(|(key, v)| infer_json_type(v, mentioned).map(|ty| (key, ty)))
+                .collect();
+            serde_json::json!({ "type": "Record", "attributes": attributes })
+        },
+        _ => return None,
+    })
+}
+
+/// The declaration of namespace `name` in a JSON schema fragment, created empty if missing
+fn schema_namespace<'a>(
+    fragment: &'a mut serde_json::Map<String, JsonValue>,
+    name: &str,
+) -> &'a mut JsonValue {
+    fragment
+        .entry(name.to_string())
+        .or_insert_with(|| serde_json::json!({ "entityTypes": {}, "actions": {} }))
+}
+
+/// Merge one sample record into inferred attributes, tracking how often each appears
+fn observe_record(
+    attributes: &mut BTreeMap<String, (JsonValue, usize)>,
+    record: &serde_json::Map<String, JsonValue>,
+    mentioned: &mut BTreeSet<String>,
+) {
+    for (name, value) in record {
+        if let Some(ty) = infer_json_type(value, mentioned) {
+            attributes.entry(name.clone()).or_insert((ty, 0)).1 += 1;
+        }
+    }
+}
+
+/// A record type from observed attributes; only those seen in every sample are required
+fn record_type(attributes: BTreeMap<String, (JsonValue, usize)>, samples: usize) -> JsonValue {
+    let attributes: serde_json::Map<String, JsonValue> = attributes
+        .into_iter()
+        .map(|(name, (mut ty, seen))| {
+            ty["required"] = JsonValue::Bool(seen == samples);
+            (name, ty)
+        })
+        .collect();
+    serde_json::json!({ "type": "Record", "attributes": attributes })
+}
+
+/// Draft a schema from example requests and the entities they use.
+///
+/// Each request is a dict with `principal`, `action`, and `resource` (uid
+/// strings or `{"type", "id"}` dicts) and an optional `context` dict. Entity
+/// types get the attributes and parent types seen in `entities`; actions
+/// apply to the principal and resource types they were requested with. An
+/// attribute is required only if every sample has it, and attributes whose
+/// type can't be told from the samples (e.g. empty sets) are left out. The
+/// result is a starting point to review, not an authoritative schema.
+#[pyfunction]
+fn infer_schema(requests: Vec<Bound<'_, PyDict>>, entities: &CedarEntities) -> PyResult<CedarSchema> {
+    type Attributes = BTreeMap<String, (JsonValue, usize)>;
+    let mut mentioned = BTreeSet::new();
+    let mut types: BTreeMap<String, (Attributes, usize, BTreeSet<String>)> = BTreeMap::new();
+    let mut actions: BTreeMap<String, (Vec<String>, Vec<String>, Attributes, usize)> = BTreeMap::new();
+
+    let document = entities
+        .entities
+        .to_json_value()
+        .map_err(|e| CedarError::JsonError(format!("Failed to serialize entities: {}", e)))?;
+    for entity in document.as_array().into_iter().flatten() {
+        let Some(type_name) = entity["uid"]["type"].as_str() else { continue };
+        let (attributes, samples, parents) = types.entry(type_name.to_string()).or_default();
+        *samples += 1;
+        if let Some(attrs) = entity["attrs"].as_object() {
+            observe_record(attributes, attrs, &mut mentioned);
+        }
+        for parent in entity["parents"].as_array().into_iter().flatten() {
+            if let Some(parent_type) = parent["type"].as_str() {
+                parents.insert(parent_type.to_string());
+                mentioned.insert(parent_type.to_string());
+            }
+        }
+    }
+
+    for request in &requests {
+        let request = py_to_json(request.as_any())?;
+        let principal = parse_entity_uid(&request_uid(&request, "principal")?, "principal")?;
+        let action = parse_entity_uid(&request_uid(&request, "action")?, "action")?;
+        let resource = parse_entity_uid(&request_uid(&request, "resource")?, "resource")?;
+        let (principal_type, resource_type) =
+            (principal.type_name().to_string(), resource.type_name().to_string());
+
+        let (principals, resources, context, samples) = actions.entry(action.to_string()).or_default();
+        *samples += 1;
+        if !principals.contains(&principal_type) {
+            principals.push(principal_type.clone());
+        }
+        if !resources.contains(&resource_type) {
+            resources.push(resource_type.clone());
+        }
+        if let Some(record) = request["context"].as_object() {
+            observe_record(context, record, &mut mentioned);
+        }
+        mentioned.extend([principal_type, resource_type]);
+    }
+
+    let mut fragment = serde_json::Map::new();
+    for name in mentioned {
+        types.entry(name).or_default();
+    }
+    for (type_name, (attributes, samples, parents)) in types {
+        let (ns, basename) = split_namespace(&type_name);
+        schema_namespace(&mut fragment, ns)["entityTypes"][basename] = serde_json::json!({
+            "memberOfTypes": parents,
+            "shape": record_type(attributes, samples),
+        });
+    }
+    for (action, (principals, resources, context, samples)) in actions {
+        let uid = parse_entity_uid(&action, "action")?;
+        let type_name = uid.type_name().to_string();
+        let (ns, _) = split_namespace(&type_name);
+        schema_namespace(&mut fragment, ns)["actions"][uid.id().unescaped()] = serde_json::json!({
+            "appliesTo": {
+                "principalTypes": principals,
+                "resourceTypes": resources,
+                "context": record_type(context, samples),
+            },
+        });
+    }
+
+    CedarSchema::new(&JsonValue::Object(fragment).to_string())
+        .map_err(|e| PyValueError::new_err(format!("Inferred schema was rejected: {}", e)))
+}
+
+/// Whether a JSON value has the shape of an entity uid: `{"type", "id"}` or `{"__entity": {...}}`
+fn is_uid_json(value: &JsonValue) -> bool {
+    let uid = value.get("__entity").unwrap_or(value);
+    uid["type"].is_string() && uid["id"].is_string()
+}
+
+/// Check the structure of an entities JSON document without building a store.
+///
+/// Returns one message per problem, prefixed with the entity's index (and uid
+/// when readable); an empty list means the document is well-formed. Attribute
+/// values and types are not checked. Extra top-level keys on a record are
+/// reported unless they start with `__`, like a `__source` marker. A document over the `set_max_input_bytes`
+/// limit raises `CedarLimitError` instead.
+#[pyfunction]
+fn validate_entities_json(entities_json: &str) -> PyResult<Vec<String>> {
+    check_input_size("Entities", entities_json.as_bytes())?;
+    Ok(guard_parse("entities", || Ok::<_, CedarError>(entities_json_problems(entities_json)))
+        .unwrap_or_else(|e| vec![e.to_string()]))
+}
+
+/// The checks behind `validate_entities_json`
+fn entities_json_problems(entities_json: &str) -> Vec<String> {
+    let document: JsonValue = match serde_json::from_str(entities_json) {
+        Ok(document) => document,
+        Err(e) => return vec![format!("invalid JSON: {}", e)],
+    };
+    let Some(entities) = document.as_array() else {
+        return vec!["top level must be a list of entities".to_string()];
+    };
+
+    let mut problems = Vec::new();
+    for (index, entity) in entities.iter().enumerate() {
+        let Some(fields) = entity.as_object() else {
+            problems.push(format!("entity {}: must be an object", index));
+            continue;
+        };
+        let label = match fields.get("uid") {
+            Some(uid) if is_uid_json(uid) => {
+                let uid = uid.get("__entity").unwrap_or(uid);
+                let (ty, id) = (uid["type"].as_str(), uid["id"].as_str());
+                format!("entity {} ({}::{:?})", index, ty.unwrap_or_default(), id.unwrap_or_default())
+            },
+            Some(_) => {
+                problems.push(format!("entity {}: uid must have string \"type\" and \"id\"", index));
+                format!("entity {}", index)
+            },
+            None => {
+                problems.push(format!("entity {}: missing uid", index));
+                format!("entity {}", index)
+            },
+        };
+        // `__`-prefixed keys are metadata markers `CedarEntities` keeps; others are likely typos
+        for key in fields.keys() {
+            if !ENTITY_RECORD_KEYS.contains(&key.as_str()) && !key.starts_with("__") {
+                problems.push(format!("{}: unknown field \"{}\"", label, key));
+            }
+        }
+        for key in ["attrs", "tags"] {
+            if fields.get(key).is_some_and(|v| !v.is_object()) {
+                problems.push(format!("{}: {} must be an object", label, key));
+            }
+        }
+        match fields.get("parents") {
+            None => {},
+            Some(JsonValue::Array(parents)) => {
+                for (i, parent) in parents.iter().enumerate() {
+                    if !is_uid_json(parent) {
+                        problems.push(format!("{}: parent {} is not an entity uid", label, i));
+                    }
+                }
+            },
+            Some(_) => problems.push(format!("{}: parents must be a list", label)),
+        }
+    }
+    problems
+}
+
+/// A single finding from `lint`
+struct LintWarning {
+    rule: &'static str,
+    severity: &'static str,
+    message: String,
+}
+
+/// Apply the built-in lint rules to one policy
+fn lint_policy(policy: &Policy) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let any_principal = policy.principal_constraint() == PrincipalConstraint::Any;
+    let any_action = policy.action_constraint() == ActionConstraint::Any;
+    let any_resource = policy.resource_constraint() == ResourceConstraint::Any;
+    let unconditional = !policy.has_non_scope_constraint();
+
+    match policy.effect() {
+        Effect::Permit if any_principal && any_action && any_resource && unconditional => {
+            warnings.push(LintWarning {
+                rule: "broad-permit",
+                severity: "high",
+                message: "permit applies to every principal, action, and resource with no condition"
+                    .to_string(),
+            });
+        }
+        Effect::Permit if any_action => warnings.push(LintWarning {
+            rule: "permit-any-action",
+            severity: "medium",
+            message: "permit applies to every action; list the actions it is meant to grant"
+                .to_string(),
+        }),
+        Effect::Forbid if any_principal && any_action && any_resource && unconditional => {
+            warnings.push(LintWarning {
+                rule: "forbid-everything",
+                severity: "high",
+                message: "forbid with no scope or condition denies every request".to_string(),
+            });
+        }
+        _ => {}
+    }
+    // A `when`/`unless` clause that is a literal boolean decides the policy by itself
+    let est = policy.to_json().ok();
+    let constant_clauses: Vec<(&str, bool)> = est
+        .iter()
+        .flat_map(|est| est["conditions"].as_array())
+        .flatten()
+        .filter_map(|clause| Some((clause["kind"].as_str()?, clause["body"]["Value"].as_bool()?)))
+        .collect();
+    let inactive = constant_clauses.iter().find(|(kind, value)| (*kind == "when") != *value);
+    if let Some((kind, value)) = inactive {
+        warnings.push(LintWarning {
+            rule: "always-inactive",
+            severity: "medium",
+            message: format!("`{} {{ {} }}` means the policy never applies", kind, value),
+        });
+    } else if let Some((kind, value)) = constant_clauses.first() {
+        warnings.push(LintWarning {
+            rule: "constant-condition",
+            severity: "low",
+            message: format!("`{} {{ {} }}` always holds; remove the clause", kind, value),
+        });
+    }
+    if policy.annotation("id").is_none() {
+        warnings.push(LintWarning {
+            rule: "missing-id",
+            severity: "low",
+            message: "policy has no @id annotation, so its id is auto-generated".to_string(),
+        });
+    }
+    warnings
+}
+
+/// Check policies for common anti-patterns.
+///
+/// Returns one dict per finding with `rule`, `severity` (`"high"`, `"medium"`,
+/// or `"low"`), `policy_id`, and `message`, ordered by policy id.
+#[pyfunction]
+fn lint(py: Python<'_>, policy_set: &CedarPolicySet) -> Vec<PyObject> {
+    let mut policies: Vec<&Policy> = policy_set.policies.policies().collect();
+    policies.sort_by_key(|p| p.id().to_string());
+
+    policies
+        .into_iter()
+        .flat_map(|policy| {
+            lint_policy(policy).into_iter().map(move |w| {
+                serde_json::json!({
+                    "rule": w.rule,
+                    "severity": w.severity,
+                    "policy_id": policy.id().to_string(),
+                    "message": w.message,
+                })
+            })
+        })
+        .map(|warning| json_to_py(py, &warning))
+        .collect()
+}
+
+/// Every entity `uid` is a member of, directly or through nested groups, sorted.
+///
+/// Parents of parents are followed all the way up, so a user in
+/// `Group::"team"` inside `Group::"org"` lists both groups. A uid the store
+/// lacks has no ancestors.
+#[pyfunction]
+fn ancestors(uid: &str, entities: &CedarEntities) -> PyResult<Vec<String>> {
+    let uid = parse_entity_uid(uid, "uid")?;
+    let mut ancestors: Vec<String> = entities
+        .entities
+        .ancestors(&uid)
+        .into_iter()
+        .flatten()
+        .map(|a| a.to_string())
+        .collect();
+    ancestors.sort();
+    Ok(ancestors)
+}
+
+/// Cap the size of policy, template, schema, and entity inputs, in bytes.
+///
+/// The `CedarPolicy`, `CedarTemplate`, `CedarSchema`, and `CedarEntities`
+/// constructors, `CedarPolicy.from_json_dict`, `CedarPolicySet.from_string`
+/// and its variants, `CedarEntities.from_bytes`, `authorize_json`,
+/// `validate_entities_json`, and the context, entities, and bundle JSON
+/// strings passed to `CedarAuthorizer` methods raise `CedarLimitError` for
+/// larger inputs before parsing them. `None` removes the cap. The setting is
+/// process-wide.
+#[pyfunction]
+#[pyo3(signature = (limit))]
+fn set_max_input_bytes(limit: Option<usize>) {
+    MAX_INPUT_BYTES.store(limit.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Make every guarded parser panic while `enabled`, to test that panics become `CedarParseError`.
+///
+/// Only built with the `test-hooks` feature, which release builds leave off.
+#[cfg(feature = "test-hooks")]
+#[pyfunction]
+#[pyo3(name = "_inject_parse_panics")]
+fn inject_parse_panics(enabled: bool) {
+    INJECT_PARSE_PANICS.store(enabled, Ordering::Relaxed);
+}
+
 /// A Python module implemented in Rust.
 #[pymodule]
-fn _rust(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+fn _rust(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<CedarPolicy>()?;
     m.add_class::<CedarPolicySet>()?;
     m.add_class::<CedarAuthorizer>()?;
+    m.add_class::<CedarSchema>()?;
+    m.add_class::<CedarTemplate>()?;
+    m.add_class::<CedarResponse>()?;
+    m.add_class::<CedarEntities>()?;
+    m.add_class::<CedarEntityUid>()?;
+    m.add_class::<CedarEntityBuilder>()?;
+    m.add_class::<PolicyStore>()?;
+    m.add_function(wrap_pyfunction!(merge_contexts, m)?)?;
+    m.add_function(wrap_pyfunction!(lint, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_entities_json, m)?)?;
+    m.add_function(wrap_pyfunction!(authorize_json, m)?)?;
+    m.add_function(wrap_pyfunction!(build_validated, m)?)?;
+    m.add_function(wrap_pyfunction!(explain_denial, m)?)?;
+    m.add_function(wrap_pyfunction!(infer_schema, m)?)?;
+    m.add_function(wrap_pyfunction!(set_max_input_bytes, m)?)?;
+    #[cfg(feature = "test-hooks")]
+    m.add_function(wrap_pyfunction!(inject_parse_panics, m)?)?;
+    m.add_function(wrap_pyfunction!(ancestors, m)?)?;
+    m.add("CedarJsonError", py.get_type_bound::<CedarJsonError>())?;
+    m.add("CedarParseError", py.get_type_bound::<CedarParseError>())?;
+    m.add("CedarAuthorizationError", py.get_type_bound::<CedarAuthorizationError>())?;
+    m.add("CedarSchemaError", py.get_type_bound::<CedarSchemaError>())?;
+    m.add("CedarLimitError", py.get_type_bound::<CedarLimitError>())?;
     Ok(())
 }
@@ -0,0 +1,1188 @@
+"""
+E2E tests for the raw CedarAuthorizer bindings.
+
+These exercise the Rust extension directly, without the Python
+Engine wrapper, so they need the extension to be built.
+"""
+
+import pytest
+from cedar_py._rust import CedarAuthorizer, CedarPolicy, CedarPolicySet
+
+
+def make_policy_set(*sources):
+    """Build a CedarPolicySet from Cedar policy sources."""
+    policy_set = CedarPolicySet()
+    for source in sources:
+        policy_set.add(CedarPolicy(source))
+    return policy_set
+
+
+@pytest.mark.e2e
+class TestMatchingScopesE2E:
+    """Scope-only pre-filtering of policies."""
+
+    def test_only_scope_matching_policies_returned(self):
+        """Policies are returned when their scope matches, regardless of conditions."""
+        policy_set = make_policy_set(
+            '@id("alice_read") permit(principal == User::"alice", action == Action::"read", resource);',
+            '@id("bob_read") permit(principal == User::"bob", action == Action::"read", resource);',
+            '@id("any_write") permit(principal, action == Action::"write", resource);',
+            '@id("docs_only") permit(principal, action, resource is Document) when { false };',
+        )
+        authorizer = CedarAuthorizer()
+
+        matched = authorizer.matching_scopes(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"'
+        )
+
+        assert matched == ["alice_read", "docs_only"]
+
+    def test_ids_sorted(self):
+        """Matching ids come back sorted whatever order the policies were added in."""
+        ids = [f"p{n:02}" for n in range(20)]
+        policy_set = make_policy_set(
+            *(f'@id("{id}") permit(principal, action, resource);' for id in reversed(ids))
+        )
+
+        assert CedarAuthorizer().matching_scopes(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"'
+        ) == ids
+
+    def test_in_scope_uses_entity_hierarchy(self):
+        """`in` constraints are resolved through the supplied entities."""
+        policy_set = make_policy_set(
+            '@id("admins") permit(principal in Group::"admins", action, resource);',
+        )
+        entities = """[
+            {"uid": {"type": "User", "id": "alice"}, "attrs": {},
+             "parents": [{"type": "Group", "id": "admins"}]}
+        ]"""
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.matching_scopes(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', entities
+        ) == ["admins"]
+        assert authorizer.matching_scopes(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"'
+        ) == []
+
+
+@pytest.mark.e2e
+class TestPermitReachabilityE2E:
+    """Partial evaluation with unconstrained principal or resource."""
+
+    def test_permit_reachable_for_some_principal(self):
+        """A permit scoped to one principal is reachable when the principal is unknown."""
+        policy_set = make_policy_set(
+            '@id("alice_read") permit(principal == User::"alice", action == Action::"read", resource);',
+        )
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.is_permit_reachable(
+            policy_set, 'Action::"read"', resource='Document::"doc1"'
+        ) is True
+        assert authorizer.is_permit_reachable(
+            policy_set, 'Action::"delete"', resource='Document::"doc1"'
+        ) is False
+
+    def test_forbid_everything_makes_permit_unreachable(self):
+        """An unconditional forbid decides the request even with unknowns."""
+        policy_set = make_policy_set(
+            '@id("alice_read") permit(principal == User::"alice", action == Action::"read", resource);',
+            '@id("lockdown") forbid(principal, action, resource);',
+        )
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.is_permit_reachable(policy_set, 'Action::"read"') is False
+
+
+@pytest.mark.e2e
+class TestRequireEntitiesE2E:
+    """Rejecting requests that reference unknown entities."""
+
+    ENTITIES = """[
+        {"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": []},
+        {"uid": {"type": "Document", "id": "doc1"}, "attrs": {}, "parents": []}
+    ]"""
+
+    def test_missing_principal_raises_when_required(self):
+        """A principal absent from the store is an error under require_entities."""
+        from cedar_py._rust import CedarAuthorizationError
+
+        policy_set = make_policy_set('permit(principal, action, resource);')
+        authorizer = CedarAuthorizer(require_entities=True)
+
+        with pytest.raises(CedarAuthorizationError, match="principal"):
+            authorizer.is_authorized(
+                policy_set, 'User::"mallory"', 'Action::"read"', 'Document::"doc1"',
+                None, self.ENTITIES,
+            )
+        assert authorizer.is_authorized(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"',
+            None, self.ENTITIES,
+        ) is True
+
+    def test_missing_principal_allowed_by_default(self):
+        """Without the flag a missing principal is evaluated normally."""
+        policy_set = make_policy_set('permit(principal, action, resource);')
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.is_authorized(
+            policy_set, 'User::"mallory"', 'Action::"read"', 'Document::"doc1"',
+            None, self.ENTITIES,
+        ) is True
+
+
+@pytest.mark.e2e
+class TestStructuredResponseE2E:
+    """The CedarResponse returned by authorize()."""
+
+    def test_request_id_is_echoed(self):
+        """The caller's request id comes back on the response."""
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+        authorizer = CedarAuthorizer()
+
+        response = authorizer.authorize(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"',
+            request_id="req-1234",
+        )
+
+        assert response.request_id == "req-1234"
+        assert response.allowed is True
+        assert response.decision == "Allow"
+        assert response.reasons == ["all"]
+        assert "req-1234" in repr(response)
+
+    def test_request_id_defaults_to_none(self):
+        """Without a request id the field is None."""
+        authorizer = CedarAuthorizer()
+
+        response = authorizer.authorize(
+            make_policy_set(), 'User::"alice"', 'Action::"read"', 'Document::"doc1"'
+        )
+
+        assert response.request_id is None
+        assert response.decision == "Deny"
+
+
+@pytest.mark.e2e
+class TestDatetimeExtensionE2E:
+    """Cedar's datetime extension in context and policies."""
+
+    POLICY = (
+        '@id("after_launch") permit(principal, action, resource) '
+        'when { context.now > datetime("2023-01-01T00:00:00Z") };'
+    )
+
+    @staticmethod
+    def context_at(timestamp):
+        return '{"now": {"__extn": {"fn": "datetime", "arg": "%s"}}}' % timestamp
+
+    def test_datetime_comparison_in_context(self):
+        """Datetime values in context compare against datetime literals."""
+        policy_set = make_policy_set(self.POLICY)
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.is_authorized(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"',
+            self.context_at("2024-01-01T00:00:00Z"),
+        ) is True
+        assert authorizer.is_authorized(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"',
+            self.context_at("2022-06-01T00:00:00Z"),
+        ) is False
+
+
+@pytest.mark.e2e
+class TestAssertDecisionE2E:
+    """Asserting expected decisions in policy test suites."""
+
+    def test_matching_decision_passes(self):
+        """A matching expectation returns the response."""
+        policy_set = make_policy_set('@id("block") forbid(principal == User::"eve", action, resource);')
+        authorizer = CedarAuthorizer()
+
+        response = authorizer.assert_decision(
+            "deny", policy_set, 'User::"eve"', 'Action::"read"', 'Document::"doc1"'
+        )
+
+        assert response.reasons == ["block"]
+
+    def test_mismatch_raises_with_details(self):
+        """A mismatch raises with the actual decision and determining policies."""
+        from cedar_py._rust import CedarAuthorizationError
+
+        policy_set = make_policy_set('@id("open") permit(principal, action, resource);')
+        authorizer = CedarAuthorizer()
+
+        with pytest.raises(CedarAuthorizationError) as excinfo:
+            authorizer.assert_decision(
+                "Deny", policy_set, 'User::"eve"', 'Action::"read"', 'Document::"doc1"'
+            )
+
+        message = str(excinfo.value)
+        assert "expected Deny but got Allow" in message
+        assert "open" in message
+
+
+@pytest.mark.e2e
+class TestReasonEffectsE2E:
+    """Determining policies reported together with their effects."""
+
+    def test_effects_accompany_reasons(self):
+        """Forbid reasons carry the forbid effect; permit reasons the permit effect."""
+        policy_set = make_policy_set(
+            '@id("open") permit(principal, action, resource);',
+            '@id("block_eve") forbid(principal == User::"eve", action, resource);',
+        )
+        authorizer = CedarAuthorizer()
+
+        allowed, reasons, errors = authorizer.is_authorized_with_effects(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"'
+        )
+        assert allowed is True
+        assert reasons == [{"id": "open", "effect": "permit"}]
+
+        allowed, reasons, errors = authorizer.is_authorized_with_effects(
+            policy_set, 'User::"eve"', 'Action::"read"', 'Document::"doc1"'
+        )
+        assert allowed is False
+        assert reasons == [{"id": "block_eve", "effect": "forbid"}]
+        assert errors == []
+
+
+@pytest.mark.e2e
+class TestConflictsE2E:
+    """Detecting requests matched by both a permit and a forbid."""
+
+    def test_overlapping_permit_and_forbid_conflict(self):
+        """Both satisfied policies are reported, grouped by effect."""
+        policy_set = make_policy_set(
+            '@id("staff_read") permit(principal, action == Action::"read", resource);',
+            '@id("block_eve") forbid(principal == User::"eve", action, resource);',
+            '@id("never") permit(principal, action, resource) when { false };',
+        )
+        authorizer = CedarAuthorizer()
+
+        conflict, permits, forbids = authorizer.conflicts(
+            policy_set, 'User::"eve"', 'Action::"read"', 'Document::"doc1"'
+        )
+
+        assert conflict is True
+        assert permits == ["staff_read"]
+        assert forbids == ["block_eve"]
+
+    def test_no_conflict_without_overlap(self):
+        """A request matched only by permits is not a conflict."""
+        policy_set = make_policy_set(
+            '@id("staff_read") permit(principal, action == Action::"read", resource);',
+            '@id("block_eve") forbid(principal == User::"eve", action, resource);',
+        )
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.conflicts(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"'
+        ) == (False, ["staff_read"], [])
+
+
+@pytest.mark.e2e
+class TestUidCacheE2E:
+    """Reusing parsed uids across repeated requests."""
+
+    def test_repeated_requests_hit_cache(self):
+        """Only the first request parses each distinct uid."""
+        policy_set = make_policy_set('permit(principal, action, resource);')
+        authorizer = CedarAuthorizer()
+
+        for _ in range(10):
+            assert authorizer.is_authorized(
+                policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"'
+            )
+
+        assert authorizer.uid_cache_info() == {"hits": 27, "misses": 3, "size": 3}
+
+    def test_cache_can_be_disabled(self):
+        """With the cache off, decisions are unchanged and no stats are kept."""
+        policy_set = make_policy_set('permit(principal == User::"alice", action, resource);')
+        authorizer = CedarAuthorizer(cache_uids=False)
+
+        assert authorizer.uid_cache_info() is None
+        assert authorizer.is_authorized(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"'
+        ) is True
+
+        authorizer.set_uid_cache(True)
+        assert authorizer.is_authorized(
+            policy_set, 'User::"bob"', 'Action::"read"', 'Document::"doc1"'
+        ) is False
+        assert authorizer.uid_cache_info()["misses"] == 3
+
+
+@pytest.mark.e2e
+class TestFastPathE2E:
+    """Short-circuiting on the first satisfied permit."""
+
+    ENTITIES = """[
+        {"uid": {"type": "User", "id": "alice"}, "attrs": {"level": 5},
+         "parents": [{"type": "Group", "id": "staff"}]},
+        {"uid": {"type": "User", "id": "bob"}, "attrs": {"level": 1},
+         "parents": [{"type": "Group", "id": "staff"}]},
+        {"uid": {"type": "User", "id": "eve"}, "attrs": {"level": 9}, "parents": []},
+        {"uid": {"type": "Group", "id": "staff"}, "attrs": {}, "parents": []}
+    ]"""
+
+    def test_agrees_with_full_authorizer(self):
+        """Every combination gets the same decision as is_authorized."""
+        from cedar_py._rust import CedarTemplate
+
+        policy_set = make_policy_set(
+            '@id("staff_read") permit(principal in Group::"staff", action == Action::"read", resource);',
+            '@id("senior_any") permit(principal, action, resource) when { principal.level > 4 };',
+            '@id("no_eve_delete") forbid(principal == User::"eve", action == Action::"delete", resource);',
+            '@id("no_secret") forbid(principal, action, resource == Document::"secret") '
+            "unless { principal.level > 8 };",
+            '@id("broken") permit(principal, action, resource) when { principal.missing };',
+        )
+        policy_set.add_template(CedarTemplate(
+            '@id("owner") permit(principal == ?principal, action, resource == ?resource);'
+        ))
+        policy_set.link("owner", "bob_doc2", {"?principal": 'User::"bob"', "?resource": 'Document::"doc2"'})
+        authorizer = CedarAuthorizer()
+
+        for principal in ("alice", "bob", "eve", "mallory"):
+            for action in ("read", "write", "delete"):
+                for resource in ("doc1", "doc2", "secret"):
+                    request = (
+                        policy_set, 'User::"%s"' % principal, 'Action::"%s"' % action,
+                        'Document::"%s"' % resource, None, self.ENTITIES,
+                    )
+                    allowed, permit_id = authorizer.is_authorized_fast(*request)
+                    assert allowed == authorizer.is_authorized(*request), request
+                    assert permit_id == authorizer.effective_permit(*request), request
+
+    def test_reports_satisfied_permit(self):
+        """The returned id is a permit that actually applies."""
+        policy_set = make_policy_set(
+            '@id("never") permit(principal, action, resource) when { false };',
+            '@id("alice") permit(principal == User::"alice", action, resource);',
+        )
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.is_authorized_fast(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"'
+        ) == (True, "alice")
+
+    def test_smallest_permit_id_wins(self):
+        """When several permits apply, the smallest id is reported, whatever the insertion order."""
+        ids = ["m", "z", "a", "q", "c"]
+        policy_set = make_policy_set(*('@id("%s") permit(principal, action, resource);' % i for i in ids))
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.is_authorized_fast(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"'
+        ) == (True, "a")
+
+
+@pytest.mark.e2e
+class TestHasOperatorE2E:
+    """`has` on optional attributes evaluates to false rather than erroring."""
+
+    ENTITIES = """[
+        {"uid": {"type": "Document", "id": "owned"},
+         "attrs": {"owner": {"__entity": {"type": "User", "id": "alice"}}}, "parents": []},
+        {"uid": {"type": "Document", "id": "orphan"}, "attrs": {}, "parents": []}
+    ]"""
+
+    def test_entity_attribute_presence(self):
+        """`resource has owner` is false for an entity lacking the attribute."""
+        policy_set = make_policy_set(
+            '@id("owner") permit(principal, action, resource) '
+            "when { resource has owner && resource.owner == principal };"
+        )
+        authorizer = CedarAuthorizer()
+
+        allowed, reasons, errors = authorizer.is_authorized_detailed(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"orphan"',
+            None, self.ENTITIES,
+        )
+        assert allowed is False
+        assert errors == []
+        assert authorizer.is_authorized(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"owned"',
+            None, self.ENTITIES,
+        ) is True
+
+    def test_entity_absent_from_store(self):
+        """`has` on an entity missing from the store is false, not an error."""
+        policy_set = make_policy_set(
+            '@id("owner") permit(principal, action, resource) unless { resource has owner };'
+        )
+        authorizer = CedarAuthorizer()
+
+        allowed, reasons, errors = authorizer.is_authorized_detailed(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"ghost"'
+        )
+        assert allowed is True
+        assert errors == []
+
+    def test_context_attribute_presence(self):
+        """`context has` distinguishes present and absent keys."""
+        policy_set = make_policy_set(
+            '@id("token") permit(principal, action, resource) '
+            'when { context has token && context.token == "ok" };'
+        )
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.is_authorized(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"', '{"token": "ok"}'
+        ) is True
+        allowed, reasons, errors = authorizer.is_authorized_detailed(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"', '{}'
+        )
+        assert allowed is False
+        assert errors == []
+
+
+@pytest.mark.e2e
+class TestEvaluationTimingE2E:
+    """Optional evaluation timing on structured responses."""
+
+    def test_timed_response_has_eval_micros(self):
+        """With timing requested the field is a non-negative integer."""
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+        authorizer = CedarAuthorizer()
+
+        response = authorizer.authorize(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"', timed=True
+        )
+
+        assert isinstance(response.eval_micros, int)
+        assert response.eval_micros >= 0
+        assert response.allowed is True
+
+    def test_untimed_response_omits_eval_micros(self):
+        """Timing is off by default."""
+        authorizer = CedarAuthorizer()
+
+        response = authorizer.authorize(
+            make_policy_set(), 'User::"alice"', 'Action::"read"', 'Document::"doc1"'
+        )
+
+        assert response.eval_micros is None
+
+
+@pytest.mark.e2e
+class TestRequestBundleE2E:
+    """Authorizing a request bundled with its entities."""
+
+    def test_bundle_decision(self):
+        """Uids, context, and entities are all taken from the bundle."""
+        import json
+
+        policy_set = make_policy_set(
+            '@id("staff_mfa") permit(principal in Group::"staff", action, resource) '
+            "when { context.mfa };"
+        )
+        bundle = {
+            "principal": 'User::"alice"',
+            "action": {"type": "Action", "id": "read"},
+            "resource": 'Document::"doc1"',
+            "context": {"mfa": True},
+            "entities": [
+                {"uid": {"type": "User", "id": "alice"}, "attrs": {},
+                 "parents": [{"type": "Group", "id": "staff"}]},
+            ],
+        }
+        authorizer = CedarAuthorizer()
+
+        response = authorizer.authorize_bundle(policy_set, json.dumps(bundle))
+        assert response.allowed is True
+        assert response.reasons == ["staff_mfa"]
+
+        del bundle["entities"]
+        assert authorizer.authorize_bundle(policy_set, json.dumps(bundle)).allowed is False
+
+
+@pytest.mark.e2e
+class TestEffectivePermissionsE2E:
+    """Enumerating what a principal may do over an entity store."""
+
+    ENTITIES = """[
+        {"uid": {"type": "User", "id": "alice"}, "attrs": {},
+         "parents": [{"type": "Group", "id": "staff"}]},
+        {"uid": {"type": "Group", "id": "staff"}, "attrs": {}, "parents": []},
+        {"uid": {"type": "Document", "id": "public"}, "attrs": {}, "parents": []},
+        {"uid": {"type": "Document", "id": "secret"}, "attrs": {}, "parents": []},
+        {"uid": {"type": "Photo", "id": "cat"}, "attrs": {}, "parents": []}
+    ]"""
+
+    @staticmethod
+    def policy_set():
+        return make_policy_set(
+            '@id("staff_read") permit(principal in Group::"staff", action == Action::"read", resource);',
+            '@id("edit_public") permit(principal == User::"alice", action == Action::"edit", '
+            'resource == Document::"public");',
+            '@id("no_secret") forbid(principal, action, resource == Document::"secret");',
+        )
+
+    def test_allowed_pairs(self):
+        """Every allowed pair is listed, forbids excluded."""
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.effective_permissions(
+            self.policy_set(), 'User::"alice"', self.ENTITIES
+        ) == [
+            ('Action::"edit"', 'Document::"public"'),
+            ('Action::"read"', 'Document::"public"'),
+            ('Action::"read"', 'Group::"staff"'),
+            ('Action::"read"', 'Photo::"cat"'),
+            ('Action::"read"', 'User::"alice"'),
+        ]
+
+    def test_filters_bound_enumeration(self):
+        """Action and resource-type filters narrow the cross product."""
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.effective_permissions(
+            self.policy_set(), 'User::"alice"', self.ENTITIES,
+            actions=['Action::"read"'], resource_type="Document",
+        ) == [('Action::"read"', 'Document::"public"')]
+
+
+@pytest.mark.e2e
+class TestEvaluateEachE2E:
+    """Per-policy outcomes for a single request."""
+
+    def test_mixed_outcomes(self):
+        """Each policy reports whether it applied, didn't, or errored."""
+        policy_set = make_policy_set(
+            '@id("open") permit(principal, action, resource);',
+            '@id("block_eve") forbid(principal == User::"eve", action, resource);',
+            '@id("bob_only") permit(principal == User::"bob", action, resource);',
+            '@id("broken") permit(principal, action, resource) when { principal.level > 1 };',
+        )
+        authorizer = CedarAuthorizer()
+
+        assert authorizer.evaluate_each(
+            policy_set, 'User::"eve"', 'Action::"read"', 'Document::"doc1"'
+        ) == {
+            "open": "permit",
+            "block_eve": "forbid",
+            "bob_only": "nomatch",
+            "broken": "error",
+        }
+
+
+@pytest.mark.e2e
+class TestAttributeOverridesE2E:
+    """Evaluating with attributes overlaid for one request."""
+
+    ENTITIES = """[
+        {"uid": {"type": "User", "id": "alice"}, "attrs": {"role": "viewer", "team": "core"},
+         "parents": []}
+    ]"""
+
+    def test_override_flips_decision(self):
+        """Overriding `role` allows a request the stored entity is denied."""
+        policy_set = make_policy_set(
+            '@id("admins") permit(principal, action, resource) '
+            'when { principal.role == "admin" && principal.team == "core" };'
+        )
+        authorizer = CedarAuthorizer()
+        request = (policy_set, 'User::"alice"', 'Action::"delete"', 'Document::"doc1"', None, self.ENTITIES)
+
+        assert authorizer.authorize(*request).allowed is False
+        assert authorizer.authorize(
+            *request, attribute_overrides={'User::"alice"': {"role": "admin"}}
+        ).allowed is True
+        assert authorizer.authorize(*request).allowed is False
+
+    def test_override_creates_missing_entity(self):
+        """An override for an entity absent from the store creates it."""
+        policy_set = make_policy_set(
+            '@id("owner") permit(principal, action, resource) when { resource.owner == principal };'
+        )
+        authorizer = CedarAuthorizer()
+
+        response = authorizer.authorize(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"',
+            attribute_overrides={
+                'Document::"doc1"': {"owner": {"__entity": {"type": "User", "id": "alice"}}},
+            },
+        )
+
+        assert response.allowed is True
+
+
+@pytest.mark.e2e
+class TestEvaluatedConditionsE2E:
+    """Counting the policies whose conditions a request reached."""
+
+    def test_count_matches_scope_matching_policies(self):
+        """Only policies whose scope matches the request are counted."""
+        policy_set = make_policy_set(
+            '@id("read_mfa") permit(principal, action == Action::"read", resource) when { context.mfa };',
+            '@id("alice_any") permit(principal == User::"alice", action, resource);',
+            '@id("bob_only") permit(principal == User::"bob", action, resource) when { true };',
+            '@id("writes") forbid(principal, action == Action::"write", resource) when { true };',
+        )
+        authorizer = CedarAuthorizer()
+
+        response = authorizer.authorize(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"', '{"mfa": false}'
+        )
+
+        assert response.evaluated_conditions == len(authorizer.matching_scopes(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Document::"doc1"'
+        )) == 2
+
+
+@pytest.mark.e2e
+class TestExtraEntitiesE2E:
+    """Per-request entities layered over a shared store."""
+
+    def test_extra_entity_enables_allow_without_mutating_base(self):
+        """A request-only entity is visible to that call alone."""
+        from cedar_py._rust import CedarEntities
+
+        base = CedarEntities(
+            '[{"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": []}]'
+        )
+        policy_set = make_policy_set(
+            '@id("session_owner") permit(principal, action, resource) when { resource.owner == principal };'
+        )
+        authorizer = CedarAuthorizer()
+        session = [{
+            "uid": {"type": "Session", "id": "s1"},
+            "attrs": {"owner": {"__entity": {"type": "User", "id": "alice"}}},
+            "parents": [],
+        }]
+        request = (policy_set, 'User::"alice"', 'Action::"resume"', 'Session::"s1"', base)
+
+        assert authorizer.authorize_with_store(*request, extra_entities=session).allowed is True
+        assert authorizer.authorize_with_store(*request).allowed is False
+        assert len(base) == 1
+
+    def test_extra_entities_as_json_string(self):
+        """The extras may also be given as a JSON string."""
+        from cedar_py._rust import CedarEntities
+
+        policy_set = make_policy_set('@id("staff") permit(principal in Group::"staff", action, resource);')
+        extra = '[{"uid": {"type": "User", "id": "bob"}, "attrs": {}, "parents": [{"type": "Group", "id": "staff"}]}]'
+
+        response = CedarAuthorizer().authorize_with_store(
+            policy_set, 'User::"bob"', 'Action::"read"', 'Doc::"d"', CedarEntities("[]"),
+            extra_entities=extra,
+        )
+
+        assert response.allowed is True
+
+
+@pytest.mark.e2e
+class TestTraceE2E:
+    """Human-readable authorization traces."""
+
+    def test_trace_shows_decision_and_policy(self):
+        """The trace names the decision and the determining policy with its source."""
+        policy_set = make_policy_set(
+            '@id("office_read") permit(principal, action == Action::"read", resource) '
+            'when { context.location == "office" };'
+        )
+
+        trace = CedarAuthorizer().trace(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', '{"location": "office"}'
+        )
+
+        assert "Decision: Allow" in trace
+        assert "office_read" in trace
+        assert 'context.location == "office"' in trace
+        assert "Errors: (none)" in trace
+
+    def test_trace_lists_errors(self):
+        """Evaluation errors appear in the trace of a denied request."""
+        policy_set = make_policy_set(
+            '@id("needs_level") permit(principal, action, resource) when { principal.level > 3 };'
+        )
+
+        trace = CedarAuthorizer().trace(policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"')
+
+        assert "Decision: Deny" in trace
+        assert "Determining policies: (none)" in trace
+        assert "needs_level" in trace.split("Errors:")[1]
+
+
+@pytest.mark.e2e
+class TestParsedSchemaArgumentE2E:
+    """Passing a prebuilt CedarSchema to authorization calls."""
+
+    SCHEMA = """
+    entity User;
+    entity Doc;
+    action read appliesTo { principal: User, resource: Doc, context: { level: Long } };
+    """
+
+    def test_one_schema_reused_across_calls(self):
+        """A single CedarSchema types the context of every request it's passed to."""
+        from cedar_py._rust import CedarSchema, CedarSchemaError
+
+        schema = CedarSchema(self.SCHEMA)
+        policy_set = make_policy_set(
+            '@id("cleared") permit(principal, action == Action::"read", resource) when { context.level >= 3 };'
+        )
+        authorizer = CedarAuthorizer()
+        request = (policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"')
+
+        assert authorizer.is_authorized(*request, '{"level": 5}', schema=schema) is True
+        assert authorizer.is_authorized(*request, '{"level": 1}', schema=schema) is False
+        assert authorizer.authorize(*request, '{"level": 4}', schema=schema).allowed is True
+        with pytest.raises(CedarSchemaError, match="level"):
+            authorizer.authorize(*request, "{}", schema=schema)
+
+    def test_schema_source_still_accepted(self):
+        """Schema text is parsed on the fly for one-off calls."""
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+
+        assert CedarAuthorizer().is_authorized(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', '{"level": 1}', schema=self.SCHEMA
+        ) is True
+
+
+@pytest.mark.e2e
+class TestJsonErrorPreviewE2E:
+    """Malformed JSON errors quote the offending input."""
+
+    def test_context_snippet_in_message(self):
+        """The bad context document appears in the CedarJsonError message."""
+        from cedar_py._rust import CedarJsonError
+
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+
+        with pytest.raises(CedarJsonError, match="request-17") as excinfo:
+            CedarAuthorizer().is_authorized(
+                policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', '{"trace": "request-17",'
+            )
+        assert "Invalid context JSON" in str(excinfo.value)
+
+    def test_long_input_truncated(self):
+        """Only the start of a long document is quoted."""
+        from cedar_py._rust import CedarJsonError
+
+        bad_entities = '[{"uid": "' + "x" * 500 + '"'
+
+        with pytest.raises(CedarJsonError) as excinfo:
+            CedarAuthorizer().is_authorized(
+                make_policy_set('@id("all") permit(principal, action, resource);'),
+                'User::"alice"', 'Action::"read"', 'Doc::"d"', None, bad_entities,
+            )
+        message = str(excinfo.value)
+        assert '[{\\"uid\\": \\"xxx' in message
+        assert "..." in message
+        assert "x" * 200 not in message
+
+
+@pytest.mark.e2e
+class TestEffectivePermitE2E:
+    """Finding the permit that actually allows a request."""
+
+    def make_set(self):
+        """A set where eve's permit is overridden by a forbid."""
+        return make_policy_set(
+            '@id("readers") permit(principal, action == Action::"read", resource);',
+            '@id("block_eve") forbid(principal == User::"eve", action, resource);',
+        )
+
+    def test_unoverridden_permit_returned(self):
+        """An allowed request names its permit."""
+        assert CedarAuthorizer().effective_permit(
+            self.make_set(), 'User::"alice"', 'Action::"read"', 'Doc::"d"'
+        ) == "readers"
+
+    def test_overridden_permit_gives_none(self):
+        """A permit overridden by a forbid isn't effective."""
+        assert CedarAuthorizer().effective_permit(
+            self.make_set(), 'User::"eve"', 'Action::"read"', 'Doc::"d"'
+        ) is None
+
+
+@pytest.mark.e2e
+class TestSlowCallbackE2E:
+    """Reporting slow authorizations."""
+
+    def test_callback_fires_above_threshold(self):
+        """With a zero threshold, every authorization is reported with its request."""
+        calls = []
+        authorizer = CedarAuthorizer(slow_ms=0.0)
+        authorizer.set_slow_callback(lambda request, elapsed_ms: calls.append((request, elapsed_ms)))
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+
+        authorizer.is_authorized(policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', '{"ip": "10.0.0.1"}')
+
+        assert len(calls) == 1
+        request, elapsed_ms = calls[0]
+        assert request == {
+            "principal": 'User::"alice"',
+            "action": 'Action::"read"',
+            "resource": 'Doc::"d"',
+            "context": {"ip": "10.0.0.1"},
+            "request_id": None,
+        }
+        assert elapsed_ms > 0
+
+    def test_request_id_reported(self):
+        """Calls given a request id pass it to the callback."""
+        from cedar_py._rust import CedarEntities
+
+        calls = []
+        authorizer = CedarAuthorizer(slow_ms=0.0)
+        authorizer.set_slow_callback(lambda request, elapsed_ms: calls.append(request))
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+        request = ('User::"alice"', 'Action::"read"', 'Doc::"d"')
+
+        authorizer.authorize(policy_set, *request, request_id="req-1")
+        authorizer.authorize_with_store(policy_set, *request, CedarEntities("[]"), request_id="req-2")
+
+        assert [c["request_id"] for c in calls] == ["req-1", "req-2"]
+
+    def test_failed_assertion_reported(self):
+        """assert_decision reports a slow call even when the assertion fails."""
+        from cedar_py._rust import CedarAuthorizationError
+
+        calls = []
+        authorizer = CedarAuthorizer(slow_ms=0.0)
+        authorizer.set_slow_callback(lambda request, elapsed_ms: calls.append(request))
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+
+        with pytest.raises(CedarAuthorizationError):
+            authorizer.assert_decision("deny", policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"')
+
+        assert [c["principal"] for c in calls] == ['User::"alice"']
+
+    def test_one_report_per_call(self):
+        """Calls running several evaluations internally are still reported once each."""
+        calls = []
+        authorizer = CedarAuthorizer(slow_ms=0.0)
+        authorizer.set_slow_callback(lambda request, elapsed_ms: calls.append(request))
+        policy_set = make_policy_set(
+            '@id("a") permit(principal, action, resource);',
+            '@id("b") permit(principal, action, resource);',
+            '@id("c") forbid(principal, action, resource) when { context.blocked };',
+        )
+        request = (policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', '{"blocked": false}')
+
+        authorizer.is_authorized_fast(*request)
+        authorizer.evaluate_each(*request)
+        authorizer.conflicts(*request)
+
+        assert [c["action"] for c in calls] == ['Action::"read"'] * 3
+
+    def test_callback_silent_below_threshold(self):
+        """A generous threshold, or no threshold at all, reports nothing."""
+        calls = []
+        authorizer = CedarAuthorizer()
+        authorizer.set_slow_callback(lambda request, elapsed_ms: calls.append(request))
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+
+        authorizer.is_authorized(policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"')
+        authorizer.slow_ms = 60_000.0
+        authorizer.is_authorized(policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"')
+
+        assert calls == []
+        assert authorizer.slow_ms == 60_000.0
+
+
+@pytest.mark.e2e
+class TestSeparateHierarchyE2E:
+    """Group memberships supplied apart from entity attributes."""
+
+    ENTITIES = '[{"uid": {"type": "User", "id": "alice"}, "attrs": {"level": 3}, "parents": []}]'
+
+    def test_hierarchy_enables_group_permit(self):
+        """A membership from the hierarchy satisfies an `in Group` scope alongside attributes."""
+        policy_set = make_policy_set(
+            '@id("staff_read") permit(principal in Group::"staff", action, resource) '
+            "when { principal.level >= 2 };"
+        )
+        authorizer = CedarAuthorizer()
+        request = (policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', None, self.ENTITIES)
+
+        assert authorizer.authorize(*request).allowed is False
+        response = authorizer.authorize(
+            *request, hierarchy={'User::"alice"': ['Group::"staff"'], 'Group::"staff"': ['Group::"all"']}
+        )
+        assert response.allowed is True
+        assert response.reasons == ["staff_read"]
+
+    def test_transitive_membership(self):
+        """Hierarchy edges compose with each other transitively."""
+        policy_set = make_policy_set('@id("everyone") permit(principal in Group::"all", action, resource);')
+
+        response = CedarAuthorizer().authorize(
+            policy_set, 'User::"bob"', 'Action::"read"', 'Doc::"d"',
+            hierarchy={'User::"bob"': ['Group::"staff"'], 'Group::"staff"': ['Group::"all"']},
+        )
+
+        assert response.allowed is True
+
+
+@pytest.mark.e2e
+class TestSuggestFixE2E:
+    """Guidance for turning a deny into an allow."""
+
+    def test_missing_permit(self):
+        """With no applicable permit, a minimal permit is suggested."""
+        policy_set = make_policy_set('@id("admins") permit(principal == User::"admin", action, resource);')
+
+        advice = CedarAuthorizer().suggest_fix(policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"')
+
+        assert advice["kind"] == "missing_permit"
+        assert advice["suggestion"] == (
+            'permit(principal == User::"alice", action == Action::"read", resource == Doc::"d");'
+        )
+        assert advice["errors"] == []
+
+    def test_blocking_forbid(self):
+        """A forbid that blocks an otherwise permitted request is named."""
+        policy_set = make_policy_set(
+            '@id("readers") permit(principal, action == Action::"read", resource);',
+            '@id("block_eve") forbid(principal == User::"eve", action, resource);',
+        )
+
+        advice = CedarAuthorizer().suggest_fix(policy_set, 'User::"eve"', 'Action::"read"', 'Doc::"d"')
+
+        assert advice["kind"] == "relax_forbid"
+        assert advice["policies"] == ["block_eve"]
+        assert advice["permits"] == ["readers"]
+        assert "block_eve" in advice["message"]
+
+    def test_allowed_request_needs_nothing(self):
+        """An allowed request gets no fix."""
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+
+        advice = CedarAuthorizer().suggest_fix(policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"')
+
+        assert advice["kind"] == "allowed"
+
+
+@pytest.mark.e2e
+class TestContextMatrixE2E:
+    """One request evaluated across candidate contexts."""
+
+    def test_decision_per_context(self):
+        """Each context gets its own decision, in order."""
+        policy_set = make_policy_set(
+            '@id("office") permit(principal, action, resource) when { context.location == "office" };'
+        )
+        entities = '[{"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": []}]'
+
+        decisions = CedarAuthorizer().authorize_context_matrix(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"',
+            [{"location": "office"}, {"location": "home"}, {}],
+            entities,
+        )
+
+        assert decisions == ["Allow", "Deny", "Deny"]
+
+
+@pytest.mark.e2e
+class TestArchiveE2E:
+    """Archiving the full Cedar response as JSON."""
+
+    def test_archive_has_cedar_response_shape(self):
+        """The document carries the decision, reasons, and per-policy errors."""
+        import json
+
+        policy_set = make_policy_set(
+            '@id("all") permit(principal, action, resource);',
+            '@id("broken") forbid(principal, action, resource) when { principal.missing };',
+        )
+
+        archive = json.loads(CedarAuthorizer().is_authorized_archive(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"'
+        ))
+
+        assert set(archive) == {"decision", "diagnostics"}
+        assert archive["decision"] == "allow"
+        assert set(archive["diagnostics"]) == {"reason", "errors"}
+        assert archive["diagnostics"]["reason"] == ["all"]
+        [error] = archive["diagnostics"]["errors"]
+        assert error["policyId"] == "broken"
+        assert set(error["error"]) == {"message"}
+
+
+@pytest.mark.e2e
+class TestDerivedContextE2E:
+    """Context keys copied from entity attributes."""
+
+    ENTITIES = """[
+        {"uid": {"type": "User", "id": "alice"}, "attrs": {"role": "admin"}, "parents": []},
+        {"uid": {"type": "User", "id": "bob"}, "attrs": {}, "parents": []}
+    ]"""
+
+    def test_policy_reads_derived_key(self):
+        """The principal's role reaches the policy as a context key."""
+        policy_set = make_policy_set(
+            '@id("admins") permit(principal, action, resource) '
+            'when { context has principal_role && context.principal_role == "admin" };'
+        )
+
+        def allowed(principal):
+            return CedarAuthorizer().authorize(
+                policy_set, principal, 'Action::"read"', 'Doc::"d"',
+                entities_json=self.ENTITIES,
+                derive_context_from={"principal_role": "principal.role"},
+            ).allowed
+
+        assert allowed('User::"alice"') is True
+        assert allowed('User::"bob"') is False
+
+    def test_supplied_key_conflicts(self):
+        """A key given in the context can't also be derived."""
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+
+        with pytest.raises(ValueError, match="principal_role"):
+            CedarAuthorizer().authorize(
+                policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"',
+                context_json='{"principal_role": "viewer"}',
+                entities_json=self.ENTITIES,
+                derive_context_from={"principal_role": "principal.role"},
+            )
+
+
+@pytest.mark.e2e
+class TestFallbackE2E:
+    """Layered policy sets with a default fallback."""
+
+    def decide(self, primary, principal):
+        """Authorize a read with a fallback that permits every read."""
+        fallback = make_policy_set('@id("default_read") permit(principal, action == Action::"read", resource);')
+        return CedarAuthorizer().is_authorized_with_fallback(
+            primary, fallback, principal, 'Action::"read"', 'Doc::"d"'
+        )
+
+    def test_unmatched_primary_falls_through(self):
+        """With no primary policy matching, the fallback's permit allows the request."""
+        primary = make_policy_set('@id("admins") permit(principal == User::"root", action, resource);')
+
+        assert self.decide(primary, 'User::"alice"') is True
+
+    def test_primary_forbid_does_not_fall_through(self):
+        """An explicit forbid in the primary denies despite the fallback."""
+        primary = make_policy_set('@id("block_eve") forbid(principal == User::"eve", action, resource);')
+
+        assert self.decide(primary, 'User::"eve"') is False
+        assert self.decide(primary, 'User::"alice"') is True
+
+
+@pytest.mark.e2e
+class TestDecisionCacheKeyE2E:
+    """Memoization keys for decisions."""
+
+    def test_key_tracks_inputs(self):
+        """The key is stable under reformatting and changes with the policies or context."""
+        from cedar_py._rust import CedarEntities
+
+        entities = CedarEntities('[{"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": []}]')
+        authorizer = CedarAuthorizer()
+
+        def key(policy_set, context):
+            """The cache key for alice reading with the given policies and context."""
+            return authorizer.decision_cache_key(
+                policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', context, entities
+            )
+
+        policy_set = make_policy_set('@id("all") permit(principal, action, resource);')
+        base = key(policy_set, '{"ip": "10.0.0.1", "mfa": true}')
+
+        same_policies = make_policy_set('@id("all")  permit(principal, action, resource);')
+        assert key(same_policies, '{ "mfa": true,  "ip": "10.0.0.1" }') == base
+        assert key(policy_set, '{"ip": "10.0.0.2", "mfa": true}') != base
+        policy_set.add(CedarPolicy('@id("block") forbid(principal, action, resource) when { context.mfa };'))
+        assert key(policy_set, '{"ip": "10.0.0.1", "mfa": true}') != base
+
+    def test_key_ignores_policy_order(self):
+        """Policies without @id give the same key whichever order they were loaded in."""
+        read = 'permit(principal, action == Action::"read", resource);'
+        block = 'forbid(principal == User::"eve", action, resource);'
+        authorizer = CedarAuthorizer()
+
+        keys = {
+            authorizer.decision_cache_key(
+                CedarPolicySet.from_string(source), 'User::"alice"', 'Action::"read"', 'Doc::"d"'
+            )
+            for source in (read + block, block + read)
+        }
+
+        assert len(keys) == 1
+
+
+@pytest.mark.e2e
+class TestContextAllowlistE2E:
+    """Restricting the context keys clients may supply."""
+
+    POLICY = '@id("trusted") permit(principal, action, resource) when { context has is_admin && context.is_admin };'
+
+    def test_extra_key_rejected(self):
+        """A key outside the allowlist raises, naming the key."""
+        from cedar_py._rust import CedarAuthorizationError
+
+        authorizer = CedarAuthorizer()
+        authorizer.set_context_allowlist(["ip"])
+        policy_set = make_policy_set(self.POLICY)
+
+        with pytest.raises(CedarAuthorizationError, match="is_admin"):
+            authorizer.is_authorized(
+                policy_set, 'User::"eve"', 'Action::"read"', 'Doc::"d"', '{"ip": "10.0.0.1", "is_admin": true}'
+            )
+        assert authorizer.is_authorized(
+            policy_set, 'User::"eve"', 'Action::"read"', 'Doc::"d"', '{"ip": "10.0.0.1"}'
+        ) is False
+
+    def test_extra_key_dropped(self):
+        """With reject=False the injected key is removed before evaluation."""
+        authorizer = CedarAuthorizer()
+        authorizer.set_context_allowlist(["ip"], reject=False)
+        policy_set = make_policy_set(self.POLICY)
+
+        assert authorizer.is_authorized(
+            policy_set, 'User::"eve"', 'Action::"read"', 'Doc::"d"', '{"ip": "10.0.0.1", "is_admin": true}'
+        ) is False
+        authorizer.set_context_allowlist(None)
+        assert authorizer.is_authorized(
+            policy_set, 'User::"eve"', 'Action::"read"', 'Doc::"d"', '{"is_admin": true}'
+        ) is True
+
+    def test_derived_keys_pass(self):
+        """Keys derived from entities aren't screened; the caller's keys still are."""
+        from cedar_py._rust import CedarAuthorizationError
+
+        entities = '[{"uid": {"type": "User", "id": "eve"}, "attrs": {"role": "admin"}, "parents": []}]'
+        policy_set = make_policy_set(
+            '@id("admins") permit(principal, action, resource) when { context.role == "admin" };'
+        )
+        authorizer = CedarAuthorizer()
+        authorizer.set_context_allowlist(["mfa"])
+
+        def authorize(context_json):
+            return authorizer.authorize(
+                policy_set, 'User::"eve"', 'Action::"read"', 'Doc::"d"', context_json, entities,
+                derive_context_from={"role": "principal.role"},
+            )
+
+        assert authorize('{"mfa": true}').allowed is True
+        with pytest.raises(CedarAuthorizationError, match="role"):
+            authorize('{"mfa": true, "role": "admin"}')
+
+    def test_reachability_checks_allowlist(self):
+        """is_permit_reachable screens the context like the other methods."""
+        from cedar_py._rust import CedarAuthorizationError
+
+        authorizer = CedarAuthorizer()
+        authorizer.set_context_allowlist(["ip"])
+        policy_set = make_policy_set(self.POLICY)
+
+        with pytest.raises(CedarAuthorizationError, match="is_admin"):
+            authorizer.is_permit_reachable(policy_set, 'Action::"read"', context_json='{"is_admin": true}')
+        authorizer.set_context_allowlist(["ip"], reject=False)
+        assert authorizer.is_permit_reachable(
+            policy_set, 'Action::"read"', context_json='{"is_admin": true}'
+        ) is False
@@ -0,0 +1,368 @@
+"""
+E2E tests for the raw CedarEntities bindings.
+
+These exercise the Rust extension directly, so they need the
+extension to be built.
+"""
+
+import pytest
+from cedar_py._rust import CedarEntities
+
+MIXED_ENTITIES = """[
+    {"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": []},
+    {"uid": {"type": "User", "id": "bob"}, "attrs": {}, "parents": []},
+    {"uid": {"type": "App::User", "id": "carol"}, "attrs": {}, "parents": []},
+    {"uid": {"type": "Group", "id": "admins"}, "attrs": {}, "parents": []},
+    {"uid": {"type": "Document", "id": "doc1"}, "attrs": {}, "parents": []},
+    {"uid": {"type": "Document", "id": "doc2"}, "attrs": {}, "parents": []},
+    {"uid": {"type": "Document", "id": "doc3"}, "attrs": {}, "parents": []}
+]"""
+
+
+@pytest.mark.e2e
+class TestEntityStatsE2E:
+    """Size and composition of an entity store."""
+
+    def test_type_counts(self):
+        """Each type is counted separately, including namespaced types."""
+        entities = CedarEntities(MIXED_ENTITIES)
+
+        assert len(entities) == 7
+        assert entities.type_counts() == {
+            "User": 2,
+            "App::User": 1,
+            "Group": 1,
+            "Document": 3,
+        }
+
+    def test_empty_store(self):
+        """An empty store has no counts."""
+        assert CedarEntities("[]").type_counts() == {}
+
+
+@pytest.mark.e2e
+class TestEntityUidE2E:
+    """Splitting entity uids into their components."""
+
+    def test_namespaced_uid_components(self):
+        """The type keeps its namespace; the id is unquoted."""
+        from cedar_py._rust import CedarEntityUid
+
+        uid = CedarEntityUid('App::User::"alice"')
+
+        assert uid.type_name() == "App::User"
+        assert uid.id() == "alice"
+        assert str(uid) == 'App::User::"alice"'
+
+    def test_invalid_uid_raises(self):
+        """Malformed uids raise a parse error."""
+        from cedar_py._rust import CedarEntityUid, CedarParseError
+
+        with pytest.raises(CedarParseError):
+            CedarEntityUid("not a uid")
+
+
+@pytest.mark.e2e
+class TestEntityBytesE2E:
+    """Persisting a parsed entity store as bytes."""
+
+    HIERARCHY = """[
+        {"uid": {"type": "User", "id": "alice"}, "attrs": {"level": 3},
+         "parents": [{"type": "Group", "id": "admins"}]},
+        {"uid": {"type": "User", "id": "bob"}, "attrs": {"level": 1}, "parents": []},
+        {"uid": {"type": "Group", "id": "admins"}, "attrs": {}, "parents": []}
+    ]"""
+
+    def test_round_trip_preserves_decisions(self):
+        """A reloaded store authorizes exactly like the original."""
+        from cedar_py._rust import CedarPolicy, PolicyStore
+
+        original = CedarEntities(self.HIERARCHY)
+        blob = original.to_bytes()
+        reloaded = CedarEntities.from_bytes(blob)
+
+        assert isinstance(blob, bytes)
+        assert len(reloaded) == len(original)
+
+        def decisions(entities):
+            store = PolicyStore(entities=entities)
+            store.add_policy(CedarPolicy(
+                '@id("admins") permit(principal in Group::"admins", action, resource) '
+                "when { principal.level > 2 };"
+            ))
+            return [
+                store.authorize('User::"%s"' % name, 'Action::"read"', 'Document::"d"').allowed
+                for name in ("alice", "bob")
+            ]
+
+        assert decisions(reloaded) == decisions(original) == [True, False]
+
+    def test_rejects_foreign_bytes(self):
+        """Bytes without the blob header are refused."""
+        from cedar_py._rust import CedarJsonError
+
+        with pytest.raises(CedarJsonError, match="header"):
+            CedarEntities.from_bytes(b"[]")
+
+
+@pytest.mark.e2e
+class TestLenientEntitiesE2E:
+    """Schema-checked entity parsing that tolerates undeclared data."""
+
+    SCHEMA = "entity User { level: Long };"
+    ENTITIES = """[
+        {"uid": {"type": "User", "id": "alice"},
+         "attrs": {"level": 3, "nickname": "al"}, "parents": []},
+        {"uid": {"type": "Device", "id": "laptop"}, "attrs": {"os": "linux"}, "parents": []}
+    ]"""
+
+    def test_undeclared_data_rejected_by_default(self):
+        """Strict parsing refuses the undeclared attribute."""
+        from cedar_py._rust import CedarJsonError, CedarSchema
+
+        with pytest.raises(CedarJsonError):
+            CedarEntities(self.ENTITIES, CedarSchema(self.SCHEMA))
+
+    def test_lenient_accepts_undeclared_data(self):
+        """Lenient parsing keeps every entity and drops the extra attribute."""
+        from cedar_py._rust import CedarPolicy, CedarSchema, PolicyStore
+
+        entities = CedarEntities(self.ENTITIES, CedarSchema(self.SCHEMA), lenient_entities=True)
+        store = PolicyStore(entities=entities)
+        store.add_policy(CedarPolicy(
+            '@id("p") permit(principal, action, resource) when { principal has nickname };'
+        ))
+
+        assert len(entities) == 2
+        assert entities.type_counts() == {"Device": 1, "User": 1}
+        assert not store.authorize('User::"alice"', 'Action::"read"', 'Device::"laptop"').allowed
+
+    def test_lenient_still_checks_declared_attributes(self):
+        """A declared attribute of the wrong type is still an error."""
+        from cedar_py._rust import CedarJsonError, CedarSchema
+
+        bad = '[{"uid": {"type": "User", "id": "bob"}, "attrs": {"level": "high"}, "parents": []}]'
+
+        with pytest.raises(CedarJsonError):
+            CedarEntities(bad, CedarSchema(self.SCHEMA), lenient_entities=True)
+
+    def test_other_entry_points_accept_lenient_entities(self):
+        """from_bytes and CedarEntityBuilder.build take the same option."""
+        from cedar_py._rust import CedarEntityBuilder, CedarJsonError, CedarSchema
+
+        schema = CedarSchema(self.SCHEMA)
+        blob = CedarEntities(self.ENTITIES).to_bytes()
+        builder = CedarEntityBuilder()
+        builder.add('User::"alice"', {"level": 3, "nickname": "al"})
+
+        with pytest.raises(CedarJsonError):
+            CedarEntities.from_bytes(blob, schema)
+        with pytest.raises(CedarJsonError):
+            builder.build(schema)
+        assert len(CedarEntities.from_bytes(blob, schema, lenient_entities=True)) == 2
+        assert len(builder.build(schema, lenient_entities=True)) == 1
+
+
+@pytest.mark.e2e
+class TestSchemaDefaultsE2E:
+    """Filling omitted attributes from schema `@default` annotations."""
+
+    SCHEMA = """
+    entity User { @default("viewer") role?: String, @default("1") level?: Long };
+    entity Document;
+    action read appliesTo { principal: User, resource: Document };
+    """
+
+    def test_omitted_attribute_receives_default(self):
+        """A policy can read a defaulted attribute the entity never set."""
+        from cedar_py._rust import CedarPolicy, CedarSchema, PolicyStore
+
+        schema = CedarSchema(self.SCHEMA)
+        entities = CedarEntities("""[
+            {"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": []},
+            {"uid": {"type": "User", "id": "bob"}, "attrs": {"role": "admin", "level": 7}, "parents": []}
+        ]""", schema)
+        store = PolicyStore(schema=schema, entities=entities)
+        store.add_policy(CedarPolicy(
+            '@id("viewers") permit(principal, action, resource) '
+            'when { principal.role == "viewer" && principal.level == 1 };'
+        ))
+
+        assert store.authorize('User::"alice"', 'Action::"read"', 'Document::"d"').allowed
+        assert not store.authorize('User::"bob"', 'Action::"read"', 'Document::"d"').allowed
+
+    def test_invalid_default_reported(self):
+        """A default that doesn't fit the declared type is a schema error."""
+        from cedar_py._rust import CedarSchema, CedarSchemaError
+
+        schema = CedarSchema('entity User { @default("lots") level?: Long };')
+
+        with pytest.raises(CedarSchemaError, match="User.level"):
+            CedarEntities('[{"uid": {"type": "User", "id": "a"}, "attrs": {}, "parents": []}]', schema)
+
+
+@pytest.mark.e2e
+class TestEntityDiffE2E:
+    """Reconciling two entity stores."""
+
+    def test_added_and_changed_entities(self):
+        """An extra entity is added; an edited attribute or parent marks a change."""
+        before = CedarEntities("""[
+            {"uid": {"type": "User", "id": "alice"}, "attrs": {"level": 1, "team": "ops"}, "parents": []},
+            {"uid": {"type": "User", "id": "bob"}, "attrs": {}, "parents": []}
+        ]""")
+        after = CedarEntities("""[
+            {"uid": {"type": "User", "id": "alice"}, "attrs": {"level": 2, "team": "ops"}, "parents": []},
+            {"uid": {"type": "User", "id": "bob"}, "attrs": {}, "parents": []},
+            {"uid": {"type": "User", "id": "carol"}, "attrs": {}, "parents": []}
+        ]""")
+
+        assert before.diff(after) == {
+            "added": ['User::"carol"'],
+            "removed": [],
+            "changed": {'User::"alice"': {"attributes": ["level"], "parents": False}},
+        }
+        assert after.diff(before)["removed"] == ['User::"carol"']
+
+    def test_parent_change_reported(self):
+        """Moving an entity to another group is a change with no attribute differences."""
+        before = CedarEntities(
+            '[{"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": [{"type": "Group", "id": "a"}]}]'
+        )
+        after = CedarEntities(
+            '[{"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": [{"type": "Group", "id": "b"}]}]'
+        )
+
+        assert before.diff(after)["changed"] == {'User::"alice"': {"attributes": [], "parents": True}}
+        assert before.diff(before) == {"added": [], "removed": [], "changed": {}}
+
+
+@pytest.mark.e2e
+class TestEntityMetadataE2E:
+    """Source markers on entity records."""
+
+    ENTITIES = """[
+        {"uid": {"type": "User", "id": "alice"}, "attrs": {"level": 3}, "parents": [],
+         "__source": "ldap://corp/alice"},
+        {"uid": {"type": "User", "id": "bob"}, "attrs": {}, "parents": []}
+    ]"""
+
+    def test_marker_ignored_by_evaluation_and_retrievable(self):
+        """A marked entity still authorizes, and get() returns the marker."""
+        from cedar_py._rust import CedarAuthorizer, CedarPolicy, CedarPolicySet
+
+        entities = CedarEntities(self.ENTITIES)
+        policy_set = CedarPolicySet()
+        policy_set.add(CedarPolicy(
+            '@id("senior") permit(principal, action, resource) when { principal.level >= 3 };'
+        ))
+
+        assert CedarAuthorizer().authorize_with_store(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', entities
+        ).allowed is True
+        record = entities.get('User::"alice"')
+        assert record["__source"] == "ldap://corp/alice"
+        assert record["attrs"] == {"level": 3}
+        assert "__source" not in entities.get('User::"bob"')
+        assert entities.get('User::"nobody"') is None
+
+    def test_marker_survives_bytes_round_trip(self):
+        """Markers are kept when the store is serialized and reloaded."""
+        restored = CedarEntities.from_bytes(CedarEntities(self.ENTITIES).to_bytes())
+
+        assert restored.get('User::"alice"')["__source"] == "ldap://corp/alice"
+
+
+@pytest.mark.e2e
+class TestEntityContentHashE2E:
+    """Cache keys for entity stores."""
+
+    def test_reordered_json_hashes_equal(self):
+        """Key order, entity order, parent order, and whitespace don't change the hash."""
+        first = CedarEntities("""[
+            {"uid": {"type": "User", "id": "alice"}, "attrs": {"level": 3, "team": "ops"},
+             "parents": [{"type": "Group", "id": "a"}, {"type": "Group", "id": "b"}]},
+            {"uid": {"type": "User", "id": "bob"}, "attrs": {}, "parents": []}
+        ]""")
+        second = CedarEntities(
+            '[{"parents":[],"attrs":{},"uid":{"id":"bob","type":"User"}},'
+            '{"parents":[{"id":"b","type":"Group"},{"id":"a","type":"Group"}],'
+            '"attrs":{"team":"ops","level":3},"uid":{"id":"alice","type":"User"}}]'
+        )
+
+        assert first.content_hash() == second.content_hash()
+        assert len(first.content_hash()) == 64
+
+    def test_changed_attribute_changes_hash(self):
+        """A different attribute value yields a different hash."""
+        base = '[{"uid": {"type": "User", "id": "alice"}, "attrs": {"level": %d}, "parents": []}]'
+
+        assert CedarEntities(base % 1).content_hash() != CedarEntities(base % 2).content_hash()
+
+
+@pytest.mark.e2e
+class TestEntityBuilderE2E:
+    """Building entities from Python values with strict types."""
+
+    def test_mixed_attribute_types_authorize(self):
+        """Longs, strings, booleans, sets, records, and references are all readable by policies."""
+        from cedar_py._rust import CedarAuthorizer, CedarEntityBuilder, CedarEntityUid, CedarPolicySet
+
+        builder = CedarEntityBuilder()
+        builder.add('User::"alice"', {
+            "level": 3,
+            "team": "ops",
+            "active": True,
+            "tags": ["a", "b"],
+            "address": {"city": "Oslo"},
+            "manager": CedarEntityUid('User::"bob"'),
+        }, parents=['Group::"staff"'])
+        builder.add('User::"bob"')
+        entities = builder.build()
+        policy_set = CedarPolicySet.from_string(
+            '@id("p") permit(principal in Group::"staff", action, resource) when { '
+            'principal.level > 2 && principal.team == "ops" && principal.active && '
+            'principal.tags.contains("b") && principal.address.city == "Oslo" && '
+            'principal.manager == User::"bob" };'
+        )
+
+        assert len(builder) == 2
+        assert CedarAuthorizer().authorize_with_store(
+            policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', entities
+        ).allowed is True
+
+    def test_ambiguous_values_rejected(self):
+        """Floats, None, and escape-like record keys raise, naming the attribute."""
+        from cedar_py._rust import CedarEntityBuilder, CedarJsonError
+
+        for attrs, path in [
+            ({"score": 1.5}, "attrs.score"),
+            ({"nested": {"x": None}}, "attrs.nested.x"),
+            ({"tags": ["a", 2.0]}, r"attrs.tags\[1\]"),
+            ({"ref": {"__entity": {"type": "User", "id": "a"}}}, "attrs.ref"),
+        ]:
+            with pytest.raises(CedarJsonError, match=path):
+                CedarEntityBuilder().add('User::"alice"', attrs)
+
+
+@pytest.mark.e2e
+class TestEntityDotE2E:
+    """Rendering the hierarchy for GraphViz."""
+
+    def test_nodes_and_direct_edges(self):
+        """Each entity is a node and each direct membership an edge."""
+        entities = CedarEntities("""[
+            {"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": [{"type": "Group", "id": "team"}]},
+            {"uid": {"type": "Group", "id": "team"}, "attrs": {}, "parents": [{"type": "Group", "id": "org"}]},
+            {"uid": {"type": "Group", "id": "org"}, "attrs": {}, "parents": []}
+        ]""")
+
+        assert entities.to_dot() == (
+            "digraph entities {\n"
+            '    "Group::\\"org\\"";\n'
+            '    "Group::\\"team\\"";\n'
+            '    "User::\\"alice\\"";\n'
+            '    "Group::\\"team\\"" -> "Group::\\"org\\"";\n'
+            '    "User::\\"alice\\"" -> "Group::\\"team\\"";\n'
+            "}\n"
+        )
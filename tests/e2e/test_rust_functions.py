@@ -0,0 +1,403 @@
+"""
+E2E tests for the module-level helper functions of the Rust extension.
+"""
+
+import json
+
+import pytest
+from cedar_py import _rust
+
+
+@pytest.mark.e2e
+class TestMergeContextsE2E:
+    """Layered context merging."""
+
+    def test_later_layers_take_precedence(self):
+        """Shared keys resolve to the last layer; nested records merge."""
+        request = {"ip": "10.0.0.1", "session": {"mfa": False, "age": 10}}
+        middleware = {"session": {"mfa": True}, "tenant": "acme"}
+        overrides = {"ip": "192.168.1.1"}
+
+        merged = _rust.merge_contexts([request, middleware, overrides])
+
+        assert merged == {
+            "ip": "192.168.1.1",
+            "session": {"mfa": True, "age": 10},
+            "tenant": "acme",
+        }
+
+    def test_non_record_values_are_overwritten(self):
+        """A record replaced by a scalar (or vice versa) takes the later value."""
+        merged = _rust.merge_contexts([{"a": {"b": 1}}, {"a": 2}, {"c": [1]}, {"c": {"d": 1}}])
+
+        assert merged == {"a": 2, "c": {"d": 1}}
+
+
+@pytest.mark.e2e
+class TestLintE2E:
+    """Rule-based policy warnings."""
+
+    @staticmethod
+    def policy_set(*sources):
+        policy_set = _rust.CedarPolicySet()
+        for source in sources:
+            policy_set.add(_rust.CedarPolicy(source))
+        return policy_set
+
+    def test_unconditional_broad_permit_flagged(self):
+        """permit(principal, action, resource) with no condition is high severity."""
+        warnings = _rust.lint(self.policy_set('@id("everyone") permit(principal, action, resource);'))
+
+        assert [(w["rule"], w["severity"], w["policy_id"]) for w in warnings] == [
+            ("broad-permit", "high", "everyone")
+        ]
+
+    def test_scoped_policy_is_clean(self):
+        """A scoped, annotated permit produces no warnings."""
+        warnings = _rust.lint(self.policy_set(
+            '@id("alice_read") permit(principal == User::"alice", action == Action::"read", resource);'
+        ))
+
+        assert warnings == []
+
+    def test_other_rules(self):
+        """Any-action permits, blanket forbids, and missing ids are reported."""
+        warnings = _rust.lint(self.policy_set(
+            '@id("alice_all") permit(principal == User::"alice", action, resource);',
+            '@id("lockdown") forbid(principal, action, resource);',
+            'permit(principal == User::"bob", action == Action::"read", resource);',
+        ))
+
+        assert {(w["rule"], w["policy_id"]) for w in warnings} == {
+            ("permit-any-action", "alice_all"),
+            ("forbid-everything", "lockdown"),
+            ("missing-id", "policy0"),
+        }
+
+    def test_constant_conditions(self):
+        """`when { false }` makes a policy inactive; `when { true }` is merely redundant."""
+        warnings = _rust.lint(self.policy_set(
+            '@id("never") permit(principal == User::"alice", action == Action::"read", resource) '
+            "when { false };",
+            '@id("always") permit(principal == User::"bob", action == Action::"read", resource) '
+            "when { true };",
+        ))
+
+        assert [(w["rule"], w["policy_id"]) for w in warnings] == [
+            ("constant-condition", "always"),
+            ("always-inactive", "never"),
+        ]
+        assert "never applies" in warnings[1]["message"]
+
+
+@pytest.mark.e2e
+class TestValidateEntitiesJsonE2E:
+    """Structural checks on entities documents."""
+
+    def test_reports_structural_problems(self):
+        """Each malformed entity gets an actionable message."""
+        document = """[
+            {"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": []},
+            {"attrs": {}},
+            {"uid": {"type": "User"}, "parents": []},
+            {"uid": {"type": "User", "id": "bob"}, "parents": {"type": "Group", "id": "g"}},
+            {"uid": {"type": "User", "id": "carol"}, "attrs": [], "parents": ["Group::admins"]},
+            {"uid": {"type": "User", "id": "dave"}, "parent": []}
+        ]"""
+
+        assert _rust.validate_entities_json(document) == [
+            "entity 1: missing uid",
+            'entity 2: uid must have string "type" and "id"',
+            'entity 3 (User::"bob"): parents must be a list',
+            'entity 4 (User::"carol"): attrs must be an object',
+            'entity 4 (User::"carol"): parent 0 is not an entity uid',
+            'entity 5 (User::"dave"): unknown field "parent"',
+        ]
+
+    def test_well_formed_document_has_no_problems(self):
+        """A valid document, including `__entity` uids, passes."""
+        document = """[
+            {"uid": {"__entity": {"type": "User", "id": "alice"}}, "attrs": {},
+             "parents": [{"type": "Group", "id": "admins"}]}
+        ]"""
+
+        assert _rust.validate_entities_json(document) == []
+        assert _rust.validate_entities_json("{}") == ["top level must be a list of entities"]
+
+    def test_metadata_keys_tolerated(self):
+        """A `__`-prefixed marker that CedarEntities keeps isn't reported as unknown."""
+        document = '[{"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": [], "__source": "ldap"}]'
+
+        assert _rust.validate_entities_json(document) == []
+        assert _rust.CedarEntities(document).get('User::"alice"')["__source"] == "ldap"
+
+
+@pytest.mark.e2e
+class TestAuthorizeJsonE2E:
+    """Authorization with JSON in and JSON out."""
+
+    POLICIES = json.dumps({
+        "staticPolicies": {
+            "mfa_read": {
+                "effect": "permit",
+                "principal": {"op": "==", "entity": {"type": "User", "id": "alice"}},
+                "action": {"op": "All"},
+                "resource": {"op": "All"},
+                "conditions": [{
+                    "kind": "when",
+                    "body": {".": {"left": {"Var": "context"}, "attr": "mfa"}},
+                }],
+            }
+        },
+        "templates": {},
+        "templateLinks": [],
+    })
+    ENTITIES = '[{"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": []}]'
+
+    def test_decision_returned_as_json(self):
+        """Uids may be strings or objects; the decision comes back as JSON."""
+        request = json.dumps({
+            "principal": {"type": "User", "id": "alice"},
+            "action": 'Action::"read"',
+            "resource": 'Document::"doc1"',
+            "context": {"mfa": True},
+        })
+
+        result = json.loads(_rust.authorize_json(self.POLICIES, request, self.ENTITIES))
+
+        assert result == {"decision": "Allow", "reasons": ["mfa_read"], "errors": []}
+
+    def test_denied_without_context(self):
+        """A failing condition denies, and the evaluation error is reported."""
+        request = json.dumps({
+            "principal": 'User::"alice"',
+            "action": 'Action::"read"',
+            "resource": 'Document::"doc1"',
+        })
+
+        result = json.loads(_rust.authorize_json(self.POLICIES, request, self.ENTITIES))
+
+        assert result["decision"] == "Deny"
+        assert len(result["errors"]) == 1
+
+
+@pytest.mark.e2e
+class TestBuildValidatedE2E:
+    """Parsing and validating a deployment's policies in one call."""
+
+    SCHEMA = """
+    entity User { age: Long };
+    entity Document;
+    action view appliesTo { principal: User, resource: Document };
+    """
+
+    def test_valid_sources_build_ready_store(self):
+        """Valid policies produce a working store and no problems."""
+        store, problems = _rust.build_validated(
+            ['@id("adults") permit(principal, action == Action::"view", resource) '
+             "when { principal.age >= 18 };"],
+            _rust.CedarSchema(self.SCHEMA),
+        )
+
+        assert problems == []
+        assert "1 policies" in repr(store)
+
+    def test_problems_are_reported(self):
+        """Parse, duplicate-id, and validation failures are all listed."""
+        store, problems = _rust.build_validated(
+            [
+                '@id("adults") permit(principal, action == Action::"view", resource) '
+                "when { principal.age >= 18 };",
+                '@id("bad_age") permit(principal, action == Action::"view", resource) '
+                'when { principal.age == "eighteen" };',
+                "not a policy",
+                '@id("adults") forbid(principal, action, resource);',
+            ],
+            _rust.CedarSchema(self.SCHEMA),
+        )
+
+        assert len(problems) == 3
+        assert problems[0].startswith("source 2:")
+        assert problems[1].startswith("source 3:")
+        assert problems[2].startswith("bad_age:")
+
+
+@pytest.mark.e2e
+class TestExplainDenialE2E:
+    """User-facing explanations of denied requests."""
+
+    @staticmethod
+    def authorize(*sources):
+        policy_set = _rust.CedarPolicySet()
+        for source in sources:
+            policy_set.add(_rust.CedarPolicy(source))
+        return _rust.CedarAuthorizer().authorize(
+            policy_set, 'User::"carl"', 'Action::"read"', 'Document::"doc1"'
+        )
+
+    def test_explicit_forbid_named(self):
+        """A forbid that denied the request is named."""
+        response = self.authorize(
+            '@id("open") permit(principal, action, resource);',
+            '@id("block-contractors") forbid(principal, action, resource);',
+        )
+
+        assert _rust.explain_denial(response) == (
+            "Access denied: forbidden by policy 'block-contractors'."
+        )
+
+    def test_default_deny(self):
+        """Without a matching permit the message says so."""
+        response = self.authorize('@id("bob") permit(principal == User::"bob", action, resource);')
+
+        assert _rust.explain_denial(response) == "Access denied: no policy permits this request."
+
+    def test_allowed_response_rejected(self):
+        """Allowed responses have nothing to explain."""
+        response = self.authorize('@id("open") permit(principal, action, resource);')
+
+        with pytest.raises(ValueError):
+            _rust.explain_denial(response)
+
+
+@pytest.mark.e2e
+class TestInferSchemaE2E:
+    """Drafting a schema from examples."""
+
+    ENTITIES = json.dumps([
+        {"uid": {"type": "User", "id": "alice"}, "attrs": {"age": 30, "email": "a@example.com"},
+         "parents": [{"type": "Group", "id": "staff"}]},
+        {"uid": {"type": "User", "id": "bob"}, "attrs": {"age": 25}, "parents": []},
+        {"uid": {"type": "Document", "id": "d1"},
+         "attrs": {"owner": {"__entity": {"type": "User", "id": "alice"}}, "tags": ["public"]}, "parents": []},
+    ])
+    REQUESTS = [
+        {"principal": 'User::"alice"', "action": 'Action::"read"', "resource": 'Document::"d1"',
+         "context": {"mfa": True, "ip_hits": 3}},
+        {"principal": {"type": "User", "id": "bob"}, "action": 'Action::"read"',
+         "resource": 'Document::"d1"', "context": {"mfa": False}},
+    ]
+
+    def test_inferred_schema_validates_examples(self):
+        """The draft declares what the samples use and accepts the same requests."""
+        from cedar_py._rust import CedarAuthorizer, CedarEntities, CedarPolicy, CedarPolicySet
+
+        schema = _rust.infer_schema(self.REQUESTS, CedarEntities(self.ENTITIES))
+
+        assert schema.entity_types() == ["Document", "Group", "User"]
+        assert schema.actions() == ['Action::"read"']
+        assert schema.completions()["attributes"]["User"] == ["age", "email"]
+
+        CedarEntities(self.ENTITIES, schema)
+        policy_set = CedarPolicySet()
+        policy_set.add(CedarPolicy(
+            '@id("owner") permit(principal, action == Action::"read", resource) '
+            "when { resource.owner == principal && context.mfa };"
+        ))
+        authorizer = CedarAuthorizer()
+        for request in self.REQUESTS:
+            principal = request["principal"]
+            if isinstance(principal, dict):
+                principal = f'{principal["type"]}::"{principal["id"]}"'
+            authorizer.authorize(
+                policy_set, principal, request["action"], request["resource"],
+                json.dumps(request["context"]), self.ENTITIES, schema=schema,
+            )
+
+    def test_attribute_missing_from_some_samples_is_optional(self):
+        """Context keys absent from some requests aren't required."""
+        from cedar_py._rust import CedarAuthorizer, CedarEntities, CedarPolicy, CedarPolicySet
+
+        schema = _rust.infer_schema(self.REQUESTS, CedarEntities(self.ENTITIES))
+        policy_set = CedarPolicySet()
+        policy_set.add(CedarPolicy('@id("all") permit(principal, action, resource);'))
+
+        assert CedarAuthorizer().is_authorized(
+            policy_set, 'User::"bob"', 'Action::"read"', 'Document::"d1"', '{"mfa": true}', schema=schema
+        ) is True
+
+
+@pytest.mark.e2e
+class TestMaxInputBytesE2E:
+    """The process-wide input size cap."""
+
+    def test_oversized_inputs_rejected(self):
+        """Inputs past the cap raise CedarLimitError; smaller ones still parse."""
+        small = "permit(principal, action, resource);"
+        large = small + " " * 100
+        _rust.set_max_input_bytes(64)
+        try:
+            assert _rust.CedarPolicy(small).id
+            with pytest.raises(_rust.CedarLimitError, match="over the limit of 64 bytes"):
+                _rust.CedarPolicy(large)
+            with pytest.raises(_rust.CedarLimitError):
+                _rust.CedarPolicySet.from_string(large)
+            with pytest.raises(_rust.CedarLimitError):
+                _rust.CedarSchema("entity User;" + " " * 100)
+            with pytest.raises(_rust.CedarLimitError):
+                _rust.CedarEntities("[" + " " * 100 + "]")
+        finally:
+            _rust.set_max_input_bytes(None)
+
+        assert _rust.CedarPolicy(large).id
+
+    def test_request_inputs_rejected(self):
+        """The cap covers JSON policies and the entities and context passed to authorizers."""
+        policy_set = _rust.CedarPolicySet()
+        policy_set.add(_rust.CedarPolicy("permit(principal, action, resource);"))
+        authorizer = _rust.CedarAuthorizer()
+        request = ('User::"alice"', 'Action::"read"', 'Document::"d1"')
+        padded = "{" + " " * 100 + "}"
+        policy_dict = {
+            "effect": "permit",
+            "principal": {"op": "All"},
+            "action": {"op": "All"},
+            "resource": {"op": "All"},
+            "conditions": [],
+            "annotations": {"note": "x" * 100},
+        }
+        _rust.set_max_input_bytes(64)
+        try:
+            with pytest.raises(_rust.CedarLimitError):
+                _rust.CedarPolicy.from_json_dict(policy_dict)
+            with pytest.raises(_rust.CedarLimitError):
+                authorizer.is_authorized(policy_set, *request, padded)
+            with pytest.raises(_rust.CedarLimitError):
+                authorizer.is_authorized(policy_set, *request, None, "[" + " " * 100 + "]")
+            with pytest.raises(_rust.CedarLimitError):
+                authorizer.authorize_bundle(policy_set, padded)
+            with pytest.raises(_rust.CedarLimitError):
+                authorizer.decision_cache_key(policy_set, *request, padded)
+            with pytest.raises(_rust.CedarLimitError):
+                authorizer.authorize_with_store(
+                    policy_set, *request, _rust.CedarEntities("[]"), extra_entities="[" + " " * 100 + "]"
+                )
+            with pytest.raises(_rust.CedarLimitError):
+                _rust.validate_entities_json("[" + " " * 100 + "]")
+            assert authorizer.is_authorized(policy_set, *request, "{}", "[]") is True
+        finally:
+            _rust.set_max_input_bytes(None)
+
+
+@pytest.mark.e2e
+class TestAncestorsE2E:
+    """Transitive group membership."""
+
+    ENTITIES = """[
+        {"uid": {"type": "User", "id": "alice"}, "attrs": {},
+         "parents": [{"type": "Group", "id": "team"}, {"type": "Group", "id": "oncall"}]},
+        {"uid": {"type": "Group", "id": "team"}, "attrs": {}, "parents": [{"type": "Group", "id": "dept"}]},
+        {"uid": {"type": "Group", "id": "dept"}, "attrs": {}, "parents": [{"type": "Group", "id": "org"}]},
+        {"uid": {"type": "Group", "id": "oncall"}, "attrs": {}, "parents": []},
+        {"uid": {"type": "Group", "id": "org"}, "attrs": {}, "parents": []},
+        {"uid": {"type": "Group", "id": "other"}, "attrs": {}, "parents": []}
+    ]"""
+
+    def test_nested_groups_listed(self):
+        """Direct and inherited groups are all listed; unrelated ones aren't."""
+        entities = _rust.CedarEntities(self.ENTITIES)
+
+        assert _rust.ancestors('User::"alice"', entities) == [
+            'Group::"dept"', 'Group::"oncall"', 'Group::"org"', 'Group::"team"',
+        ]
+        assert _rust.ancestors('User::"nobody"', entities) == []
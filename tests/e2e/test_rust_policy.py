@@ -0,0 +1,387 @@
+"""
+E2E tests for the raw CedarPolicy bindings.
+
+These exercise the Rust extension directly, without the Python
+Policy wrapper, so they need the extension to be built.
+"""
+
+import pytest
+from cedar_py import _rust
+from cedar_py._rust import CedarPolicy
+
+JSON_POLICY = """{
+    "effect": "permit",
+    "principal": {"op": "==", "entity": {"type": "User", "id": "alice"}},
+    "action": {"op": "All"},
+    "resource": {"op": "All"},
+    "conditions": []
+}"""
+
+
+@pytest.mark.e2e
+class TestPolicyIdE2E:
+    """Policy id assignment."""
+
+    def test_explicit_id_for_cedar_source(self):
+        """An explicit id overrides the @id annotation."""
+        policy = CedarPolicy(
+            '@id("annotated") permit(principal, action, resource);',
+            policy_id="external-42",
+        )
+
+        assert policy.id == "external-42"
+
+    def test_explicit_id_for_json(self):
+        """An explicit id is applied to JSON policies too."""
+        policy = CedarPolicy(JSON_POLICY, policy_id="from-json")
+
+        assert policy.id == "from-json"
+
+    def test_annotation_used_without_explicit_id(self):
+        """Without an explicit id the @id annotation still applies."""
+        policy = CedarPolicy('@id("annotated") permit(principal, action, resource);')
+
+        assert policy.id == "annotated"
+
+
+@pytest.mark.e2e
+class TestConditionE2E:
+    """Extracting a policy's when/unless clauses."""
+
+    def test_when_clause_returned(self):
+        """The condition clause is returned as written."""
+        policy = CedarPolicy(
+            '@id("mfa") permit(principal == User::"alice", action, resource)\n'
+            "when { context.mfa == true };"
+        )
+
+        assert policy.condition() == "when { context.mfa == true }"
+
+    def test_multiple_clauses_preserved(self):
+        """Both when and unless clauses come back in order."""
+        policy = CedarPolicy(
+            'permit(principal, action, resource) when { context.mfa } '
+            'unless { context.ip == "0.0.0.0" };'
+        )
+
+        assert policy.condition() == 'when { context.mfa } unless { context.ip == "0.0.0.0" }'
+
+    def test_no_condition_is_empty(self):
+        """A scope-only policy has an empty condition."""
+        policy = CedarPolicy('permit(principal == User::"a)b", action, resource);')
+
+        assert policy.condition() == ""
+
+
+@pytest.mark.e2e
+class TestContextKeysE2E:
+    """Finding the context attributes a policy depends on."""
+
+    def test_accessed_keys_reported(self):
+        """Attribute accesses and `has` tests both count; nested keys don't."""
+        policy = CedarPolicy(
+            'permit(principal, action, resource) when { context.mfa && context has ip } '
+            'unless { context.ip.isLoopback() || context.session.risk > 5 || principal.ip == "x" };'
+        )
+
+        assert policy.context_keys() == ["ip", "mfa", "session"]
+
+    def test_no_context_access(self):
+        """A policy without context references reports nothing."""
+        assert CedarPolicy("permit(principal, action, resource);").context_keys() == []
+
+
+@pytest.mark.e2e
+class TestScopeAccessorsE2E:
+    """Structured access to scope constraints."""
+
+    def test_eq_principal(self):
+        """An equality constraint reports the uid."""
+        policy = CedarPolicy('permit(principal == User::"alice", action, resource);')
+
+        assert policy.principal_scope() == {"op": "eq", "entity": 'User::"alice"'}
+        assert policy.action_scope() == {"op": "any", "actions": []}
+        assert policy.resource_scope() == {"op": "any"}
+
+    def test_in_and_is_constraints(self):
+        """`in`, `is`, and `is ... in` constraints carry uids and types."""
+        policy = CedarPolicy(
+            'permit(principal is User in Group::"staff", '
+            'action in [Action::"read", Action::"list"], resource is Document);'
+        )
+
+        assert policy.principal_scope() == {
+            "op": "is_in", "entity_type": "User", "entity": 'Group::"staff"',
+        }
+        assert policy.action_scope() == {
+            "op": "in", "actions": ['Action::"read"', 'Action::"list"'],
+        }
+        assert policy.resource_scope() == {"op": "is", "entity_type": "Document"}
+
+    def test_action_list_and_group(self):
+        """A list of actions and a single action group are told apart."""
+        listed = CedarPolicy('permit(principal, action in [Action::"view", Action::"edit"], resource);')
+        grouped = CedarPolicy('permit(principal, action in Action::"writers", resource);')
+
+        assert listed.action_scope() == {"op": "in", "actions": ['Action::"view"', 'Action::"edit"']}
+        assert grouped.action_scope() == {"op": "in_group", "actions": ['Action::"writers"']}
+
+
+@pytest.mark.e2e
+class TestPolicyReprE2E:
+    """Readable representations for debugging."""
+
+    def test_repr_shows_id_and_effect(self):
+        """repr names the id and effect; str is the Cedar source."""
+        source = '@id("view-photos") permit(principal, action == Action::"view", resource);'
+        policy = CedarPolicy(source)
+
+        assert repr(policy) == "CedarPolicy(id='view-photos', effect='permit')"
+        assert 'action == Action::"view"' in str(policy)
+        assert repr(CedarPolicy('forbid(principal, action, resource);', "deny_all")) == (
+            "CedarPolicy(id='deny_all', effect='forbid')"
+        )
+
+
+@pytest.mark.e2e
+class TestWithIdE2E:
+    """Renaming policies for import."""
+
+    def test_with_id_returns_renamed_copy(self):
+        """The copy has the new id; the original is unchanged."""
+        original = CedarPolicy('@id("view") permit(principal, action, resource);')
+
+        renamed = original.with_id("imported-view")
+
+        assert renamed.id == "imported-view"
+        assert original.id == "view"
+        assert renamed.condition() == original.condition()
+
+
+@pytest.mark.e2e
+class TestPriorityE2E:
+    """The @priority display convention."""
+
+    def test_priority_read_from_annotation(self):
+        """The annotation is parsed as an integer; absent means None."""
+        assert CedarPolicy('@id("a") @priority("10") permit(principal, action, resource);').priority() == 10
+        assert CedarPolicy('@id("b") permit(principal, action, resource);').priority() is None
+
+    def test_non_integer_priority_raises(self):
+        """A priority that isn't an integer is rejected."""
+        from cedar_py._rust import CedarParseError
+
+        with pytest.raises(CedarParseError, match="priority"):
+            CedarPolicy('@id("a") @priority("high") permit(principal, action, resource);').priority()
+
+    def test_set_sorted_by_priority(self):
+        """Lowest priority first, unprioritized last, ties by id."""
+        from cedar_py._rust import CedarPolicySet
+
+        policy_set = CedarPolicySet()
+        for source in [
+            '@id("late") @priority("50") permit(principal, action, resource);',
+            '@id("none") permit(principal, action, resource);',
+            '@id("first") @priority("-1") forbid(principal, action, resource);',
+            '@id("mid_b") @priority("10") permit(principal, action, resource);',
+            '@id("mid_a") @priority("10") permit(principal, action, resource);',
+        ]:
+            policy_set.add(CedarPolicy(source))
+
+        assert policy_set.sorted_by_priority() == ["first", "mid_a", "mid_b", "late", "none"]
+
+
+@pytest.mark.e2e
+class TestUsesExtensionsE2E:
+    """Reporting extension function usage."""
+
+    def test_ip_functions_reported(self):
+        """Both the constructor and methods on its result are listed."""
+        policy = CedarPolicy(
+            '@id("office_net") permit(principal, action, resource) '
+            'when { ip(context.source).isInRange(ip("10.0.0.0/8")) };'
+        )
+
+        assert policy.uses_extensions() == ["ip", "isInRange"]
+
+    def test_decimal_and_datetime_reported(self):
+        """Other extensions are detected alongside each other."""
+        policy = CedarPolicy(
+            '@id("limits") permit(principal, action, resource) '
+            'when { context.amount.lessThan(decimal("10.5")) && context.now < datetime("2030-01-01") };'
+        )
+
+        assert policy.uses_extensions() == ["datetime", "decimal", "lessThan"]
+
+    def test_plain_policy_uses_none(self):
+        """A policy with no extension calls reports nothing."""
+        policy = CedarPolicy(
+            '@id("office") permit(principal, action, resource) when { context.location == "office" };'
+        )
+
+        assert policy.uses_extensions() == []
+
+
+@pytest.mark.e2e
+class TestAdversarialInputE2E:
+    """Malformed input raises ordinary exceptions instead of panicking."""
+
+    POLICIES = [
+        "",
+        "\x00\x00",
+        "permit(",
+        'permit(principal == User::"unterminated, action, resource);',
+        "permit(principal, action, resource) when { ((((((((1 ",
+        '@id("' + "é" * 50 + "permit(principal, action, résource);",
+        "forbid(principal is ?principal, action, resource);",
+        "{",
+        '{"effect": "permit", "principal": {"op": "=="}}',
+        '{"effect": 7, "conditions": [null]}',
+    ]
+
+    def test_policies_raise_value_error(self):
+        """Every adversarial policy is rejected with a ValueError."""
+        for source in self.POLICIES:
+            with pytest.raises(ValueError):
+                CedarPolicy(source)
+
+    def test_other_parsers_raise_value_error(self):
+        """Templates, policy sets, schemas, and entities reject bad input the same way."""
+        from cedar_py._rust import CedarEntities, CedarPolicySet, CedarSchema, CedarTemplate
+
+        bad_inputs = [
+            (CedarTemplate, "permit(principal == ?principal, action, resource) when { ?resource };"),
+            (CedarPolicySet.from_string, 'permit(principal, action, resource); @id("x"'),
+            (CedarSchema, "entity User in [User, ; action"),
+            (CedarSchema, '{"": {"entityTypes": {"A": {"shape": {"type": "Nope"}}}, "actions": {}}}'),
+            (CedarEntities, '[{"uid": {"__entity": 1}, "attrs": [], "parents": {}}]'),
+            (CedarEntities, '[{"uid": {"type": "", "id": ""}, "attrs": {}, "parents": []}]'),
+        ]
+        for parse, source in bad_inputs:
+            with pytest.raises(ValueError):
+                parse(source)
+
+    @pytest.mark.skipif(
+        not hasattr(_rust, "_inject_parse_panics"), reason="needs a build with the test-hooks feature"
+    )
+    def test_parser_panics_become_parse_errors(self):
+        """A panic in any guarded parser surfaces as CedarParseError, not PanicException."""
+        entities = '[{"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": []}]'
+        blob = _rust.CedarEntities(entities).to_bytes()
+        policy_set = _rust.CedarPolicySet()
+        authorizer = _rust.CedarAuthorizer()
+        request = ('User::"alice"', 'Action::"read"', 'Doc::"d"')
+        parsers = [
+            lambda: CedarPolicy("permit(principal, action, resource);"),
+            lambda: CedarPolicy.from_json_dict({"effect": "permit"}),
+            lambda: _rust.CedarTemplate("permit(principal == ?principal, action, resource);"),
+            lambda: _rust.CedarTemplate.check("permit(principal, action, resource);"),
+            lambda: _rust.CedarPolicySet.from_string("permit(principal, action, resource);"),
+            lambda: _rust.CedarSchema("entity User;"),
+            lambda: _rust.CedarEntities(entities),
+            lambda: _rust.CedarEntities.from_bytes(blob),
+            lambda: authorizer.is_authorized(policy_set, *request),
+            lambda: authorizer.authorize_bundle(policy_set, '{"entities": []}'),
+            lambda: _rust.authorize_json("{}", "{}", entities),
+        ]
+
+        _rust._inject_parse_panics(True)
+        try:
+            for parse in parsers:
+                with pytest.raises(_rust.CedarParseError, match="Internal error while parsing"):
+                    parse()
+            problems = _rust.validate_entities_json(entities)
+        finally:
+            _rust._inject_parse_panics(False)
+
+        assert len(problems) == 1 and "Internal error while parsing entities" in problems[0]
+        assert authorizer.is_authorized(policy_set, *request) is False
+
+    def test_template_check_reports_instead_of_raising(self):
+        """check() turns malformed input into problem messages."""
+        from cedar_py._rust import CedarTemplate
+
+        assert CedarTemplate.check("permit(principal == ?principal, action") != []
+
+
+@pytest.mark.e2e
+class TestPolicyFormatE2E:
+    """Choosing the policy input format explicitly."""
+
+    CEDAR = '@id("read") permit(principal, action == Action::"read", resource);'
+    JSON = (
+        '{"effect": "permit", "principal": {"op": "All"}, '
+        '"action": {"op": "==", "entity": {"type": "Action", "id": "read"}}, '
+        '"resource": {"op": "All"}, "conditions": []}'
+    )
+
+    def test_explicit_cedar(self):
+        """format="cedar" parses Cedar source, and refuses JSON."""
+        assert CedarPolicy(self.CEDAR, format="cedar").id == "read"
+        with pytest.raises(ValueError):
+            CedarPolicy(self.JSON, format="cedar")
+
+    def test_explicit_json(self):
+        """format="json" parses Cedar's JSON policy format."""
+        policy = CedarPolicy(self.JSON, "read_json", format="json")
+
+        assert policy.id == "read_json"
+        assert policy.action_scope() == {"op": "eq", "actions": ['Action::"read"']}
+
+    def test_auto_matches_heuristic(self):
+        """The default still picks the format from the leading character."""
+        assert CedarPolicy(self.CEDAR).id == "read"
+        assert CedarPolicy(self.JSON, "j").action_scope()["op"] == "eq"
+
+    def test_ambiguous_input_disambiguated(self):
+        """JSON with a byte-order mark is misread by auto but accepted as explicit JSON."""
+        with_bom = "\ufeff" + self.JSON
+
+        with pytest.raises(ValueError):
+            CedarPolicy(with_bom, "bom")
+        assert CedarPolicy(with_bom, "bom", format="json").id == "bom"
+
+    def test_unknown_format_rejected(self):
+        """Only the three documented formats are accepted."""
+        with pytest.raises(ValueError, match="format"):
+            CedarPolicy(self.CEDAR, format="yaml")
+
+
+@pytest.mark.e2e
+class TestFromJsonDictE2E:
+    """Building policies from dict-form JSON ASTs."""
+
+    AST = {
+        "effect": "forbid",
+        "principal": {"op": "==", "entity": {"type": "User", "id": "eve"}},
+        "action": {"op": "All"},
+        "resource": {"op": "All"},
+        "conditions": [],
+        "annotations": {"id": "block_eve"},
+    }
+
+    def test_id_and_effect_from_dict(self):
+        """The id comes from the `id` annotation and the effect from the AST."""
+        policy = CedarPolicy.from_json_dict(self.AST)
+
+        assert policy.id == "block_eve"
+        assert repr(policy) == "CedarPolicy(id='block_eve', effect='forbid')"
+        assert policy.principal_scope() == {"op": "eq", "entity": 'User::"eve"'}
+
+    def test_explicit_id_wins(self):
+        """An explicit id overrides the annotation."""
+        assert CedarPolicy.from_json_dict(self.AST, "renamed").id == "renamed"
+
+    def test_same_id_as_json_string(self):
+        """A JSON string and the equivalent dict get the same id, with or without an override."""
+        import json
+
+        text = json.dumps(self.AST)
+
+        assert CedarPolicy(text).id == CedarPolicy.from_json_dict(self.AST).id == "block_eve"
+        assert CedarPolicy(text, "renamed").id == CedarPolicy.from_json_dict(self.AST, "renamed").id
+
+    def test_invalid_ast_raises(self):
+        """A dict that isn't a policy AST is rejected."""
+        with pytest.raises(ValueError, match="from JSON"):
+            CedarPolicy.from_json_dict({"effect": "maybe"})
@@ -0,0 +1,757 @@
+"""
+E2E tests for the raw CedarPolicySet bindings.
+
+These exercise the Rust extension directly, without the Python
+PolicySet wrapper, so they need the extension to be built.
+"""
+
+import pytest
+from cedar_py._rust import CedarPolicy, CedarPolicySet
+
+SCHEMA = """
+entity User { age: Long };
+entity Document;
+action view appliesTo { principal: User, resource: Document };
+"""
+
+
+def make_policy_set(*sources):
+    """Build a CedarPolicySet from Cedar policy sources."""
+    policy_set = CedarPolicySet()
+    for source in sources:
+        policy_set.add(CedarPolicy(source))
+    return policy_set
+
+
+@pytest.mark.e2e
+class TestValidateE2E:
+    """Schema validation with structured errors."""
+
+    def test_valid_policies_have_no_errors(self):
+        """A well-typed policy set validates cleanly."""
+        policy_set = make_policy_set(
+            '@id("adults") permit(principal, action == Action::"view", resource) '
+            "when { principal.age >= 18 };"
+        )
+
+        assert policy_set.validate(SCHEMA) == []
+
+    def test_type_mismatch_reports_policy_id(self):
+        """A type error is attributed to the offending policy."""
+        policy_set = make_policy_set(
+            '@id("adults") permit(principal, action == Action::"view", resource) '
+            "when { principal.age >= 18 };",
+            '@id("bad_age") permit(principal, action == Action::"view", resource) '
+            'when { principal.age == "eighteen" || principal.age > "x" };',
+        )
+
+        errors = policy_set.validate(SCHEMA)
+
+        assert errors
+        assert {e["policy_id"] for e in errors} == {"bad_age"}
+        assert all(e["kind"] and e["message"] for e in errors)
+        assert any(e["location"] is not None for e in errors)
+
+
+@pytest.mark.e2e
+class TestCloneE2E:
+    """Copying policy sets."""
+
+    def test_mutating_clone_leaves_original_unchanged(self):
+        """Removing a policy from a clone doesn't touch the original."""
+        original = make_policy_set(
+            '@id("a") permit(principal, action, resource);',
+            '@id("b") forbid(principal, action, resource);',
+        )
+
+        copy = original.clone()
+        copy.remove("a")
+
+        assert len(copy) == 1
+        assert len(original) == 2
+
+    def test_deepcopy_supported(self):
+        """copy.deepcopy produces an independent policy set."""
+        import copy
+
+        original = make_policy_set('@id("a") permit(principal, action, resource);')
+
+        duplicate = copy.deepcopy(original)
+        duplicate.remove("a")
+
+        assert len(duplicate) == 0
+        assert len(original) == 1
+
+    def test_is_empty(self):
+        """A fresh set is empty; adding a policy or template makes it non-empty."""
+        from cedar_py._rust import CedarTemplate
+
+        policy_set = CedarPolicySet()
+        assert policy_set.is_empty() is True
+
+        policy_set.add(CedarPolicy('@id("a") permit(principal, action, resource);'))
+        assert policy_set.is_empty() is False
+
+        templates_only = CedarPolicySet()
+        templates_only.add_template(CedarTemplate('@id("t") permit(principal == ?principal, action, resource);'))
+        assert templates_only.is_empty() is False
+
+
+@pytest.mark.e2e
+class TestPoliciesReferencingE2E:
+    """Finding policies that mention an entity."""
+
+    def test_scope_and_condition_references_found(self):
+        """References in either the scope or a condition count."""
+        policy_set = make_policy_set(
+            '@id("admin_all") permit(principal == User::"admin", action, resource);',
+            '@id("owned_by_admin") permit(principal, action, resource) '
+            'when { resource.owner == User::"admin" };',
+            '@id("alice_read") permit(principal == User::"alice", action, resource);',
+        )
+
+        assert policy_set.policies_referencing('User::"admin"') == [
+            "admin_all",
+            "owned_by_admin",
+        ]
+        assert policy_set.policies_referencing('Group::"admin"') == []
+
+
+@pytest.mark.e2e
+class TestContentHashE2E:
+    """Order- and formatting-independent policy set hashes."""
+
+    READ = '@id("read") permit(principal, action == Action::"read", resource);'
+    BLOCK = '@id("block") forbid(principal == User::"eve", action, resource);'
+
+    def test_insertion_order_and_whitespace_ignored(self):
+        """The same policies added in another order, reformatted, hash equal."""
+        first = make_policy_set(self.READ, self.BLOCK)
+        second = make_policy_set(
+            '@id("block")\nforbid (\n  principal == User::"eve",\n  action,\n  resource\n);',
+            self.READ,
+        )
+
+        assert first.content_hash() == second.content_hash()
+        assert len(first.content_hash()) == 64
+
+    def test_content_change_alters_hash(self):
+        """Changing any policy changes the hash."""
+        original = make_policy_set(self.READ, self.BLOCK)
+        changed = make_policy_set(
+            self.READ, '@id("block") forbid(principal == User::"mallory", action, resource);'
+        )
+
+        assert original.content_hash() != changed.content_hash()
+
+    def test_generated_ids_ignored(self):
+        """Policies without @id hash the same whichever order they were parsed in."""
+        read = 'permit(principal, action == Action::"read", resource);'
+        block = 'forbid(principal == User::"eve", action, resource);'
+
+        first = CedarPolicySet.from_string(read + block)
+        second = CedarPolicySet.from_string(block + read)
+
+        assert first.content_hash() == second.content_hash()
+
+
+@pytest.mark.e2e
+class TestStringLiteralsE2E:
+    """Extracting hardcoded strings from policies."""
+
+    def test_literals_from_conditions(self):
+        """Comparisons, sets, records, and like patterns all contribute literals."""
+        policy_set = make_policy_set(
+            '@id("office") permit(principal == User::"alice", action, resource) '
+            'when { context.location == "office" && context.tags.contains("public") };',
+            '@id("mail") forbid(principal, action, resource) '
+            'when { principal.email like "*@example.com" || context.meta == {"tier": "gold"} }'
+            ' unless { ["admin", "office"].contains(context.role) };',
+        )
+
+        assert policy_set.string_literals() == [
+            "@example.com", "admin", "gold", "office", "public",
+        ]
+
+
+@pytest.mark.e2e
+class TestAddOnConflictE2E:
+    """Choosing what happens when an added policy's id is taken."""
+
+    ORIGINAL = '@id("p") permit(principal == User::"alice", action, resource);'
+    REPLACEMENT = '@id("p") permit(principal == User::"bob", action, resource);'
+
+    def test_error_mode_raises(self):
+        """The default keeps raising on a duplicate id."""
+        policy_set = make_policy_set(self.ORIGINAL)
+
+        with pytest.raises(ValueError, match="'p'"):
+            policy_set.add(CedarPolicy(self.REPLACEMENT))
+        with pytest.raises(ValueError, match="'p'"):
+            policy_set.add(CedarPolicy(self.REPLACEMENT), on_conflict="error")
+
+    def test_skip_mode_keeps_existing(self):
+        """Skipping reports False and leaves the original in place."""
+        policy_set = make_policy_set(self.ORIGINAL)
+
+        assert policy_set.add(CedarPolicy(self.REPLACEMENT), on_conflict="skip") is False
+        assert len(policy_set) == 1
+        assert policy_set.policies_referencing('User::"alice"') == ["p"]
+
+    def test_replace_mode_swaps_policy(self):
+        """Replacing removes the old policy and adds the new one."""
+        policy_set = make_policy_set(self.ORIGINAL)
+
+        assert policy_set.add(CedarPolicy(self.REPLACEMENT), on_conflict="replace") is True
+        assert len(policy_set) == 1
+        assert policy_set.policies_referencing('User::"bob"') == ["p"]
+        assert policy_set.policies_referencing('User::"alice"') == []
+
+    def test_unknown_mode_rejected(self):
+        """Only the three documented modes are accepted."""
+        with pytest.raises(ValueError, match="on_conflict"):
+            CedarPolicySet().add(CedarPolicy(self.ORIGINAL), on_conflict="merge")
+
+
+@pytest.mark.e2e
+class TestFromStringE2E:
+    """Building a set from a multi-policy source."""
+
+    def test_annotated_ids_used(self):
+        """`@id` annotations name the policies; unannotated ones get positional ids."""
+        policy_set = CedarPolicySet.from_string(
+            '@id("read") permit(principal, action == Action::"read", resource);\n'
+            'forbid(principal == User::"eve", action, resource);\n'
+        )
+
+        assert len(policy_set) == 2
+        assert policy_set.policies_referencing('User::"eve"') == ["policy1"]
+        assert policy_set.policies_referencing('Action::"read"') == ["read"]
+
+    def test_duplicate_id_raises(self):
+        """Two policies sharing an `@id` are rejected, naming the id."""
+        from cedar_py._rust import CedarParseError
+
+        with pytest.raises(CedarParseError, match="'x'"):
+            CedarPolicySet.from_string(
+                '@id("x") permit(principal, action, resource);\n'
+                '@id("x") forbid(principal, action, resource);\n'
+            )
+
+    def test_prefix_applied_to_every_id(self):
+        """With a prefix, annotated, positional, and template ids are all namespaced."""
+        policy_set = CedarPolicySet.from_string_with_prefix(
+            '@id("read") permit(principal, action == Action::"read", resource);\n'
+            'forbid(principal == User::"eve", action, resource);\n'
+            '@id("owner") permit(principal == ?principal, action, resource);\n',
+            "acme/",
+        )
+
+        ids = policy_set.policies_referencing('Action::"read"') + policy_set.policies_referencing('User::"eve"')
+        assert ids == ["acme/read", "acme/policy1"]
+        assert list(policy_set.links_by_template()) == ["acme/owner"]
+        assert all(i.startswith("acme/") for i in ids)
+
+
+@pytest.mark.e2e
+class TestLoadE2E:
+    """Loading a mixed policy file with a summary."""
+
+    def test_mixed_file_counts(self):
+        """Policies and templates are counted, and unannotated ids reported."""
+        policy_set, summary = CedarPolicySet.load(
+            '@id("read") permit(principal, action == Action::"read", resource);\n'
+            'forbid(principal == User::"eve", action, resource);\n'
+            '@id("owner") permit(principal == ?principal, action, resource == ?resource);\n'
+            'permit(principal in ?principal, action == Action::"view", resource);\n'
+        )
+
+        assert summary["policies"] == 2
+        assert summary["templates"] == 2
+        assert summary["generated_ids"] == ["policy1", "policy3"]
+        assert len(policy_set) == 2
+        assert sorted(policy_set.links_by_template()) == ["owner", "policy3"]
+
+
+@pytest.mark.e2e
+class TestStripAnnotationE2E:
+    """Redacting an annotation across a policy set."""
+
+    def test_annotation_removed_logic_kept(self):
+        """The stripped set matches one written without the annotation and still authorizes."""
+        from cedar_py._rust import CedarAuthorizer
+
+        annotated = make_policy_set(
+            '@id("read") @owner("team-secret") permit(principal, action == Action::"read", resource);',
+            '@id("block") @owner("team-secret") forbid(principal == User::"eve", action, resource);',
+        )
+        plain = make_policy_set(
+            '@id("read") permit(principal, action == Action::"read", resource);',
+            '@id("block") forbid(principal == User::"eve", action, resource);',
+        )
+        assert annotated.content_hash() != plain.content_hash()
+
+        annotated.strip_annotation("owner")
+
+        assert annotated.content_hash() == plain.content_hash()
+        authorizer = CedarAuthorizer()
+        assert authorizer.is_authorized(annotated, 'User::"alice"', 'Action::"read"', 'Doc::"d"') is True
+        assert authorizer.is_authorized(annotated, 'User::"eve"', 'Action::"read"', 'Doc::"d"') is False
+
+
+@pytest.mark.e2e
+class TestMaxPoliciesE2E:
+    """Capping the size of a policy set."""
+
+    def test_limit_raises_dedicated_error(self):
+        """Going past the cap raises CedarLimitError, distinct from parse errors."""
+        from cedar_py._rust import CedarLimitError, CedarParseError, CedarTemplate
+
+        policy_set = make_policy_set('@id("a") permit(principal, action, resource);')
+        policy_set.add_template(CedarTemplate('@id("t") permit(principal == ?principal, action, resource);'))
+        policy_set.set_max_policies(2)
+        policy_set.add(CedarPolicy('@id("b") permit(principal, action, resource);'))
+
+        with pytest.raises(CedarLimitError, match="2 policies"):
+            policy_set.add(CedarPolicy('@id("c") permit(principal, action, resource);'))
+        with pytest.raises(CedarLimitError):
+            policy_set.link("t", "alice", {"?principal": 'User::"alice"'})
+        assert not issubclass(CedarLimitError, CedarParseError)
+        assert len(policy_set) == 2
+
+    def test_replace_allowed_at_limit(self):
+        """Replacing an existing policy doesn't grow the set, so it's allowed."""
+        policy_set = make_policy_set('@id("a") permit(principal, action, resource);')
+        policy_set.set_max_policies(1)
+
+        assert policy_set.add(CedarPolicy('@id("a") forbid(principal, action, resource);'), on_conflict="replace")
+
+        policy_set.set_max_policies(None)
+        policy_set.add(CedarPolicy('@id("b") permit(principal, action, resource);'))
+        assert len(policy_set) == 2
+
+
+@pytest.mark.e2e
+class TestUnreachablePoliciesE2E:
+    """Flagging policies the schema makes impossible."""
+
+    def test_impossible_scope_flagged(self):
+        """A permit for an action on a resource type it never applies to is unreachable."""
+        from cedar_py._rust import CedarSchema
+
+        policy_set = make_policy_set(
+            '@id("adults") permit(principal, action == Action::"view", resource) '
+            "when { principal.age >= 18 };",
+            '@id("view_users") permit(principal, action == Action::"view", resource is User);',
+            '@id("never") permit(principal, action, resource) when { false };',
+        )
+
+        assert policy_set.unreachable_policies(CedarSchema(SCHEMA)) == ["never", "view_users"]
+
+
+@pytest.mark.e2e
+class TestPoliciesForActionGroupE2E:
+    """Grouping policies by the action group they touch."""
+
+    SCHEMA = """
+    entity User;
+    entity Document;
+    action reading;
+    action view, download in [reading] appliesTo { principal: User, resource: Document };
+    action edit appliesTo { principal: User, resource: Document };
+    """
+
+    def test_group_and_member_policies_found(self):
+        """Policies on the group, on a member, or on any action are listed; others aren't."""
+        from cedar_py._rust import CedarSchema
+
+        policy_set = make_policy_set(
+            '@id("group") permit(principal, action in Action::"reading", resource);',
+            '@id("member") permit(principal, action == Action::"download", resource);',
+            '@id("any") forbid(principal == User::"eve", action, resource);',
+            '@id("editing") permit(principal, action == Action::"edit", resource);',
+        )
+
+        assert policy_set.policies_for_action_group('Action::"reading"', CedarSchema(self.SCHEMA)) == [
+            "any", "group", "member",
+        ]
+
+    def test_non_group_rejected(self):
+        """An action that isn't a group is an error."""
+        from cedar_py._rust import CedarSchema
+
+        with pytest.raises(ValueError, match="not an action group"):
+            CedarPolicySet().policies_for_action_group('Action::"edit"', CedarSchema(self.SCHEMA))
+
+
+@pytest.mark.e2e
+class TestDeterministicOrderE2E:
+    """Stable policy ordering for snapshots."""
+
+    SOURCES = [
+        '@id("zeta") permit(principal, action, resource);',
+        '@id("alpha") forbid(principal == User::"eve", action, resource);',
+        '@id("mid") permit(principal, action == Action::"read", resource);',
+    ]
+
+    def test_same_order_across_constructions(self):
+        """Ids, iteration, and repr are sorted by id regardless of insertion order."""
+        forward = make_policy_set(*self.SOURCES)
+        backward = make_policy_set(*reversed(self.SOURCES))
+
+        assert forward.policy_ids() == backward.policy_ids() == ["alpha", "mid", "zeta"]
+        assert [p.id for p in forward] == [p.id for p in backward] == ["alpha", "mid", "zeta"]
+        assert repr(forward) == repr(backward) == "CedarPolicySet(3 policies: [\"alpha\", \"mid\", \"zeta\"])"
+
+
+@pytest.mark.e2e
+class TestCoverageReportE2E:
+    """Tabulating who can do what."""
+
+    def test_rows_describe_each_policy(self):
+        """Each policy yields a row with its effect, rendered scopes, and condition."""
+        policy_set = make_policy_set(
+            '@id("staff_view") permit(principal in Group::"staff", action == Action::"view", resource is Photo) '
+            "when { resource.public };",
+            '@id("block_eve") forbid(principal == User::"eve", '
+            'action in [Action::"edit", Action::"delete"], resource);',
+        )
+
+        assert policy_set.coverage_report() == [
+            {
+                "id": "block_eve",
+                "effect": "forbid",
+                "principal": '== User::"eve"',
+                "action": 'in [Action::"edit", Action::"delete"]',
+                "resource": "any",
+                "condition": "",
+            },
+            {
+                "id": "staff_view",
+                "effect": "permit",
+                "principal": 'in Group::"staff"',
+                "action": '== Action::"view"',
+                "resource": "is Photo",
+                "condition": "when { resource.public }",
+            },
+        ]
+
+
+@pytest.mark.e2e
+class TestReloadE2E:
+    """Replacing a set's policies in place."""
+
+    FIRST = '@id("v1") permit(principal == User::"alice", action, resource);'
+    SECOND = '@id("v2") permit(principal, action, resource);'
+
+    def test_reload_replaces_policies(self):
+        """The reloaded source fully replaces the old policies."""
+        policy_set = CedarPolicySet.from_string(self.FIRST)
+        policy_set.reload_from_string(self.SECOND)
+
+        assert policy_set.policy_ids() == ["v2"]
+
+    def test_parse_error_keeps_set(self):
+        """A source that fails to parse leaves the current policies in place."""
+        from cedar_py._rust import CedarParseError
+
+        policy_set = CedarPolicySet.from_string(self.FIRST)
+
+        with pytest.raises(CedarParseError):
+            policy_set.reload_from_string("permit(")
+        assert policy_set.policy_ids() == ["v1"]
+
+    def test_reload_while_authorizing(self):
+        """Reloads land mid-authorization, and each authorization sees one whole version."""
+        import sys
+        import threading
+        import time
+
+        from cedar_py._rust import CedarAuthorizer
+
+        def version(name):
+            return "\n".join(
+                f'@id("{name}{i}") permit(principal, action, resource) when {{ context.n > {-i} }};'
+                for i in range(100)
+            )
+
+        versions = [version("a"), version("b")]
+        policy_set = CedarPolicySet.from_string(versions[0])
+        authorizer = CedarAuthorizer()
+        reloads, seen, overlapped, failures = [0], set(), [], []
+        stop = threading.Event()
+
+        def reload():
+            while not stop.is_set():
+                try:
+                    policy_set.reload_from_string(versions[reloads[0] % 2])
+                except BaseException as e:  # e.g. the set still borrowed by an authorization
+                    failures.append(e)
+                reloads[0] += 1
+                time.sleep(0)
+
+        def authorize():
+            for _ in range(25):
+                before = reloads[0]
+                try:
+                    response = authorizer.authorize(
+                        policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', '{"n": 1}'
+                    )
+                except BaseException as e:  # a panic surfaces as a BaseException
+                    failures.append(e)
+                    continue
+                overlapped.append(reloads[0] != before)
+                seen.add(frozenset(reason[0] for reason in response.reasons))
+
+        # Without preemption, the reloader can only run while an authorization releases the GIL
+        interval = sys.getswitchinterval()
+        sys.setswitchinterval(100)
+        try:
+            reloader = threading.Thread(target=reload)
+            readers = [threading.Thread(target=authorize) for _ in range(4)]
+            reloader.start()
+            for reader in readers:
+                reader.start()
+            for reader in readers:
+                reader.join()
+            stop.set()
+            reloader.join()
+        finally:
+            sys.setswitchinterval(interval)
+
+        assert failures == []
+        assert any(overlapped)
+        assert seen <= {frozenset("a"), frozenset("b")}
+
+    def test_settings_change_while_authorizing(self):
+        """Authorizer settings and store contents can change while another thread authorizes."""
+        import sys
+        import threading
+        import time
+
+        from cedar_py._rust import CedarAuthorizer, PolicyStore
+
+        sources = "\n".join(
+            f'@id("p{i}") permit(principal, action, resource) when {{ context.n > {-i} }};'
+            for i in range(2000)
+        )
+        policy_set = CedarPolicySet.from_string(sources)
+        store = PolicyStore(policy_set)
+        authorizer = CedarAuthorizer()
+        changes, overlapped, failures = [0], [], []
+        stop = threading.Event()
+
+        def change():
+            while not stop.is_set():
+                i = changes[0]
+                try:
+                    authorizer.slow_ms = float(i)
+                    authorizer.set_slow_callback(None)
+                    authorizer.set_context_allowlist(["n"] if i % 2 else None)
+                    authorizer.set_uid_cache(i % 2 == 0)
+                    store.add_policy(
+                        CedarPolicy(f'@id("extra{i}") forbid(principal, action, resource) when {{ false }};')
+                    )
+                except BaseException as e:  # "Already borrowed" while an authorization runs
+                    failures.append(e)
+                changes[0] += 1
+                time.sleep(0)
+
+        def authorize():
+            for _ in range(10):
+                before = changes[0]
+                try:
+                    assert authorizer.is_authorized(
+                        policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"', '{"n": 1}'
+                    )
+                    assert store.authorize('User::"alice"', 'Action::"read"', 'Doc::"d"', '{"n": 1}').allowed
+                except BaseException as e:
+                    failures.append(e)
+                    continue
+                overlapped.append(changes[0] != before)
+
+        # As above, the writer can only run while an authorization releases the GIL
+        interval = sys.getswitchinterval()
+        sys.setswitchinterval(100)
+        try:
+            writer = threading.Thread(target=change)
+            readers = [threading.Thread(target=authorize) for _ in range(2)]
+            writer.start()
+            for reader in readers:
+                reader.start()
+            for reader in readers:
+                reader.join()
+            stop.set()
+            writer.join()
+        finally:
+            sys.setswitchinterval(interval)
+
+        assert failures == []
+        assert any(overlapped)
+
+
+@pytest.mark.e2e
+class TestUnconstrainedPermitsE2E:
+    """Finding permits with open scopes."""
+
+    def test_open_scope_flagged(self):
+        """Permits with a bare principal or resource are listed; scoped permits and forbids aren't."""
+        policy_set = make_policy_set(
+            '@id("everyone") permit(principal, action == Action::"view", resource is Photo);',
+            '@id("any_doc") permit(principal == User::"alice", action, resource);',
+            '@id("scoped") permit(principal in Group::"staff", action, resource in Album::"a");',
+            '@id("block") forbid(principal, action, resource);',
+        )
+
+        assert policy_set.unconstrained_permits() == ["any_doc", "everyone"]
+
+
+@pytest.mark.e2e
+class TestBindSchemaE2E:
+    """A schema attached to the set for later calls."""
+
+    BAD_AGE = (
+        '@id("bad_age") permit(principal, action == Action::"view", resource) '
+        'when { principal.age == "x" };'
+    )
+
+    def test_validate_uses_bound_schema(self):
+        """validate() with no argument checks against the bound schema."""
+        from cedar_py._rust import CedarSchema
+
+        policy_set = make_policy_set(self.BAD_AGE)
+        policy_set.bind_schema(CedarSchema(SCHEMA))
+
+        assert {e["policy_id"] for e in policy_set.validate()} == {"bad_age"}
+        assert policy_set.clone().validate() == policy_set.validate()
+
+    def test_explicit_schema_takes_precedence(self):
+        """A schema passed to validate() overrides the bound one."""
+        policy_set = make_policy_set(self.BAD_AGE)
+        policy_set.bind_schema(SCHEMA)
+
+        assert policy_set.validate(SCHEMA.replace("age: Long", "age: String")) == []
+
+    def test_action_group_uses_bound_schema(self):
+        """policies_for_action_group() with no schema resolves the group in the bound one."""
+        policy_set = make_policy_set(
+            '@id("group") permit(principal, action in Action::"reading", resource);',
+            '@id("editing") permit(principal, action == Action::"edit", resource);',
+        )
+        policy_set.bind_schema(TestPoliciesForActionGroupE2E.SCHEMA)
+
+        assert policy_set.policies_for_action_group('Action::"reading"') == ["group"]
+
+    def test_no_schema_raises(self):
+        """Without a bound or explicit schema, validate() raises ValueError."""
+        with pytest.raises(ValueError, match="bind_schema"):
+            make_policy_set(self.BAD_AGE).validate()
+
+    def test_add_validated(self):
+        """A conforming policy is added; a non-conforming one raises and isn't."""
+        from cedar_py._rust import CedarSchemaError
+
+        policy_set = CedarPolicySet()
+        policy_set.bind_schema(SCHEMA)
+        policy_set.add_validated(CedarPolicy(
+            '@id("adults") permit(principal, action == Action::"view", resource) '
+            "when { principal.age >= 18 };"
+        ))
+
+        with pytest.raises(CedarSchemaError, match="bad_age"):
+            policy_set.add_validated(CedarPolicy(self.BAD_AGE))
+        assert policy_set.policy_ids() == ["adults"]
+
+
+@pytest.mark.e2e
+class TestShadowedPermitsE2E:
+    """Finding permits made redundant by broader ones."""
+
+    def test_narrow_permit_shadowed(self):
+        """A narrow permit under a broad, unconditional one is reported; unrelated ones aren't."""
+        policy_set = make_policy_set(
+            '@id("staff_all") permit(principal in Group::"staff", action, resource);',
+            '@id("staff_view") permit(principal in Group::"staff", action == Action::"view", resource is Photo);',
+            '@id("alice_edit") permit(principal == User::"alice", action == Action::"edit", resource) '
+            "when { resource.owner == principal };",
+            '@id("admins") permit(principal in Group::"admins", action, resource) when { context.mfa };',
+        )
+
+        assert policy_set.shadowed_permits() == [{"shadowed": "staff_view", "shadowing": "staff_all"}]
+
+    def test_conditions_must_match(self):
+        """A broader permit with a different condition doesn't shadow; an identical one does, once."""
+        policy_set = make_policy_set(
+            '@id("a") permit(principal, action, resource) when { context.mfa };',
+            '@id("b") permit(principal, action == Action::"view", resource) when { context.internal };',
+            '@id("c") permit(principal, action, resource) when { context.mfa };',
+        )
+
+        assert policy_set.shadowed_permits() == [{"shadowed": "c", "shadowing": "a"}]
+
+
+@pytest.mark.e2e
+class TestPermitsSupersetE2E:
+    """Regression checks between old and new policy sets."""
+
+    OLD = (
+        '@id("view") permit(principal, action == Action::"view", resource);',
+        '@id("admin_edit") permit(principal in Group::"admins", action == Action::"edit", resource);',
+    )
+    SAMPLES = [
+        {"principal": 'User::"alice"', "action": 'Action::"view"', "resource": 'Doc::"d"'},
+        {"principal": 'User::"alice"', "action": 'Action::"edit"', "resource": 'Doc::"d"'},
+        {"principal": {"type": "User", "id": "bob"}, "action": 'Action::"edit"', "resource": 'Doc::"d"'},
+    ]
+
+    def entities(self):
+        """Alice is an admin; bob is not."""
+        from cedar_py._rust import CedarEntities
+
+        return CedarEntities("""[
+            {"uid": {"type": "User", "id": "alice"}, "attrs": {}, "parents": [{"type": "Group", "id": "admins"}]},
+            {"uid": {"type": "User", "id": "bob"}, "attrs": {}, "parents": []}
+        ]""")
+
+    def test_broader_set_is_superset(self):
+        """A refactor that merges and widens permits still allows everything."""
+        new = make_policy_set(
+            self.OLD[0],
+            '@id("admin_all") permit(principal in Group::"admins", action, resource);',
+        )
+
+        assert new.permits_superset_of(make_policy_set(*self.OLD), self.SAMPLES, self.entities()) is True
+
+    def test_dropped_permit_detected(self):
+        """Losing the admin edit permit fails the check."""
+        new = make_policy_set(self.OLD[0])
+
+        assert new.permits_superset_of(make_policy_set(*self.OLD), self.SAMPLES, self.entities()) is False
+
+
+@pytest.mark.e2e
+class TestPartitionByNamespaceE2E:
+    """Splitting a multi-app policy set."""
+
+    def test_policies_grouped_by_namespace(self):
+        """Single-namespace policies get their own part; mixed and entity-free ones are shared."""
+        policy_set = make_policy_set(
+            '@id("photos_view") permit(principal == Photos::User::"alice", '
+            'action == Photos::Action::"view", resource is Photos::Photo);',
+            '@id("photos_owner") permit(principal, action, resource) '
+            'when { resource.owner == Photos::User::"bob" };',
+            '@id("billing_pay") permit(principal in Billing::Group::"finance", '
+            'action == Billing::Action::"pay", resource);',
+            '@id("cross_app") permit(principal == Photos::User::"alice", '
+            'action == Billing::Action::"pay", resource);',
+            '@id("everyone") permit(principal, action, resource);',
+        )
+
+        parts = policy_set.partition_by_namespace()
+
+        assert {name: part.policy_ids() for name, part in parts.items()} == {
+            "Photos": ["photos_owner", "photos_view"],
+            "Billing": ["billing_pay"],
+            "*": ["cross_app", "everyone"],
+        }
+        assert len(policy_set) == 5
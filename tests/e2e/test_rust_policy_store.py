@@ -0,0 +1,147 @@
+"""
+E2E tests for the raw PolicyStore bindings.
+
+These exercise the Rust extension directly, so they need the
+extension to be built.
+"""
+
+import pytest
+from cedar_py._rust import (
+    CedarEntities,
+    CedarPolicy,
+    CedarPolicySet,
+    CedarSchema,
+    CedarTemplate,
+    PolicyStore,
+)
+
+SCHEMA = """
+entity User in [Group];
+entity Group;
+entity Document;
+action view appliesTo { principal: User, resource: Document, context: { mfa: Bool } };
+"""
+
+ENTITIES = """[
+    {"uid": {"type": "User", "id": "alice"}, "attrs": {},
+     "parents": [{"type": "Group", "id": "editors"}]},
+    {"uid": {"type": "User", "id": "bob"}, "attrs": {}, "parents": []},
+    {"uid": {"type": "Group", "id": "editors"}, "attrs": {}, "parents": []},
+    {"uid": {"type": "Document", "id": "doc1"}, "attrs": {}, "parents": []}
+]"""
+
+
+@pytest.mark.e2e
+class TestPolicyStoreE2E:
+    """End-to-end authorization through a single store object."""
+
+    def test_authorize_end_to_end(self):
+        """Policies, a linked template, schema, and entities work together."""
+        schema = CedarSchema(SCHEMA)
+        policy_set = CedarPolicySet()
+        policy_set.add(CedarPolicy(
+            '@id("editors_view") permit(principal in Group::"editors", '
+            'action == Action::"view", resource) when { context.mfa };'
+        ))
+        store = PolicyStore(policy_set, schema, CedarEntities(ENTITIES, schema))
+        store.add_template(CedarTemplate(
+            '@id("viewer") permit(principal == ?principal, action == Action::"view", resource == ?resource);'
+        ))
+        store.link("viewer", "bob_doc1", {"?principal": 'User::"bob"', "?resource": 'Document::"doc1"'})
+
+        alice = store.authorize('User::"alice"', 'Action::"view"', 'Document::"doc1"', '{"mfa": true}')
+        bob = store.authorize('User::"bob"', 'Action::"view"', 'Document::"doc1"', '{"mfa": false}')
+        denied = store.authorize('User::"alice"', 'Action::"view"', 'Document::"doc1"', '{"mfa": false}')
+
+        assert alice.allowed and alice.reasons == ["editors_view"]
+        assert bob.allowed and bob.reasons == ["bob_doc1"]
+        assert not denied.allowed
+
+    def test_schema_rejects_invalid_request(self):
+        """With a schema set, a context of the wrong shape is rejected."""
+        store = PolicyStore(schema=CedarSchema(SCHEMA), entities=CedarEntities(ENTITIES))
+
+        with pytest.raises(ValueError):
+            store.authorize('User::"alice"', 'Action::"view"', 'Document::"doc1"', '{"mfa": "yes"}')
+
+    def test_missing_required_context_attribute(self):
+        """Omitting a context attribute the action requires names the attribute."""
+        from cedar_py._rust import CedarSchemaError
+
+        schema = CedarSchema("""
+        entity User;
+        entity Account;
+        type TransferContext = { amount: Long, memo?: String };
+        action transfer appliesTo { principal: User, resource: Account, context: TransferContext };
+        """)
+        store = PolicyStore(schema=schema)
+        store.add_policy(CedarPolicy('permit(principal, action, resource);'))
+
+        with pytest.raises(CedarSchemaError, match="amount"):
+            store.authorize('User::"alice"', 'Action::"transfer"', 'Account::"a1"', '{"memo": "rent"}')
+        assert store.authorize(
+            'User::"alice"', 'Action::"transfer"', 'Account::"a1"', '{"amount": 5}'
+        ).allowed
+
+
+@pytest.mark.e2e
+class TestPolicyStoreFromFilesE2E:
+    """Loading a store from policy, schema, and entity files."""
+
+    POLICIES = (
+        '@id("editors_view") permit(principal in Group::"editors", '
+        'action == Action::"view", resource) when { context.mfa };\n'
+    )
+
+    @staticmethod
+    def write(tmp_path, policies, schema=SCHEMA, entities=ENTITIES):
+        paths = []
+        for name, content in [("policies.cedar", policies), ("schema.cedarschema", schema),
+                              ("entities.json", entities)]:
+            path = tmp_path / name
+            path.write_text(content)
+            paths.append(str(path))
+        return paths
+
+    def test_load_and_authorize(self, tmp_path):
+        """All three files are wired into a working store."""
+        store = PolicyStore.from_files(*self.write(tmp_path, self.POLICIES))
+
+        response = store.authorize('User::"alice"', 'Action::"view"', 'Document::"doc1"', '{"mfa": true}')
+
+        assert response.allowed
+        assert response.reasons == ["editors_view"]
+
+    def test_validation_error_names_policy_file(self, tmp_path):
+        """A policy that fails validation is reported against the policies file."""
+        from cedar_py._rust import CedarSchemaError
+
+        paths = self.write(
+            tmp_path,
+            '@id("typo") permit(principal, action == Action::"view", resource) when { context.mfaa };',
+        )
+
+        with pytest.raises(CedarSchemaError, match="policies.cedar") as excinfo:
+            PolicyStore.from_files(*paths)
+        assert "typo" in str(excinfo.value)
+
+    def test_bad_entities_name_entities_file(self, tmp_path):
+        """A parse failure is reported against the file it came from."""
+        from cedar_py._rust import CedarJsonError
+
+        paths = self.write(tmp_path, self.POLICIES, entities="[{")
+
+        with pytest.raises(CedarJsonError, match="entities.json"):
+            PolicyStore.from_files(*paths)
+
+    def test_lenient_entities(self, tmp_path):
+        """An undeclared attribute fails strict loading and is tolerated with lenient_entities."""
+        from cedar_py._rust import CedarJsonError
+
+        entities = ENTITIES.replace('"id": "bob"}, "attrs": {}', '"id": "bob"}, "attrs": {"nickname": "b"}')
+        paths = self.write(tmp_path, self.POLICIES, entities=entities)
+
+        with pytest.raises(CedarJsonError, match="entities.json"):
+            PolicyStore.from_files(*paths)
+        store = PolicyStore.from_files(*paths, lenient_entities=True)
+        assert store.authorize('User::"alice"', 'Action::"view"', 'Document::"doc1"', '{"mfa": true}').allowed
@@ -0,0 +1,146 @@
+"""
+E2E tests for the raw CedarSchema bindings.
+
+These exercise the Rust extension directly, so they need the
+extension to be built.
+"""
+
+import pytest
+from cedar_py._rust import CedarSchema
+
+PHOTO_SCHEMA = """
+entity User in [Group];
+entity Group;
+entity Photo { owner: User };
+action view appliesTo { principal: User, resource: Photo };
+action delete appliesTo { principal: User, resource: Photo };
+"""
+
+
+@pytest.mark.e2e
+class TestSchemaIntrospectionE2E:
+    """Listing what a schema declares."""
+
+    def test_actions_and_entity_types(self):
+        """Both declared actions and all three entity types are listed."""
+        schema = CedarSchema(PHOTO_SCHEMA)
+
+        assert schema.actions() == ['Action::"delete"', 'Action::"view"']
+        assert schema.entity_types() == ["Group", "Photo", "User"]
+
+    def test_json_schema_accepted(self):
+        """Schemas in JSON form are parsed too."""
+        schema = CedarSchema(
+            '{"": {"entityTypes": {"User": {}}, "actions": {"view": {}}}}'
+        )
+
+        assert schema.actions() == ['Action::"view"']
+        assert schema.entity_types() == ["User"]
+
+
+@pytest.mark.e2e
+class TestSchemaCompletionsE2E:
+    """Editor completion data derived from a schema."""
+
+    def test_completions_for_photo_schema(self):
+        """Types, actions, and per-type attributes are grouped."""
+        schema = CedarSchema(PHOTO_SCHEMA)
+
+        assert schema.completions() == {
+            "entity_types": ["Group", "Photo", "User"],
+            "actions": ['Action::"delete"', 'Action::"view"'],
+            "attributes": {"Group": [], "Photo": ["owner"], "User": []},
+        }
+
+    def test_namespaced_common_type_shape(self):
+        """Attributes declared through a common type resolve, with qualified type names."""
+        schema = CedarSchema("""{"App": {
+            "commonTypes": {"Profile": {"type": "Record", "attributes": {
+                "email": {"type": "String"},
+                "level": {"type": "Long", "required": false}
+            }}},
+            "entityTypes": {"User": {"shape": {"type": "Profile"}}},
+            "actions": {}
+        }}""")
+
+        assert schema.completions()["attributes"] == {"App::User": ["email", "level"]}
+
+
+@pytest.mark.e2e
+class TestActionsForResourceTypeE2E:
+    """Listing the actions a resource type supports."""
+
+    SCHEMA = PHOTO_SCHEMA + """
+    entity Album;
+    action createAlbum appliesTo { principal: User, resource: Album };
+    """
+
+    def test_photo_actions(self):
+        """Photo supports view and delete, but not createAlbum."""
+        schema = CedarSchema(self.SCHEMA)
+
+        assert schema.actions_for_resource_type("Photo") == ['Action::"delete"', 'Action::"view"']
+        assert schema.actions_for_resource_type("Album") == ['Action::"createAlbum"']
+
+    def test_unused_or_unknown_type_has_no_actions(self):
+        """A type no action applies to, declared or not, gets an empty list."""
+        schema = CedarSchema(self.SCHEMA)
+
+        assert schema.actions_for_resource_type("Group") == []
+        assert schema.actions_for_resource_type("Nope") == []
+
+
+@pytest.mark.e2e
+class TestValidateContextE2E:
+    """Checking a context against an action's declared shape."""
+
+    SCHEMA = """
+    entity User;
+    entity Doc;
+    action read appliesTo {
+        principal: User,
+        resource: Doc,
+        context: { mfa: Bool, level: Long, device?: { os: String }, tags?: Set<String> }
+    };
+    """
+
+    def test_matching_context_passes(self):
+        """A context with every required attribute, correctly typed, has no problems."""
+        schema = CedarSchema(self.SCHEMA)
+
+        assert schema.validate_context('Action::"read"', {"mfa": True, "level": 2}) == []
+        assert schema.validate_context(
+            'Action::"read"', {"mfa": False, "level": 0, "device": {"os": "linux"}, "tags": ["a"]}
+        ) == []
+
+    def test_missing_required_key(self):
+        """A missing required attribute is reported by path."""
+        schema = CedarSchema(self.SCHEMA)
+
+        assert schema.validate_context('Action::"read"', {"mfa": True}) == [
+            "context.level: missing required attribute",
+        ]
+
+    def test_wrong_types(self):
+        """Mistyped values, including nested ones, are reported with expected and actual kinds."""
+        schema = CedarSchema(self.SCHEMA)
+
+        problems = schema.validate_context(
+            'Action::"read"', {"mfa": "yes", "level": 1, "device": {"os": 7}, "tags": ["a", 2]}
+        )
+
+        assert sorted(problems) == [
+            "context.device.os: expected String, got Long",
+            "context.mfa: expected Boolean, got String",
+            "context.tags[1]: expected String, got Long",
+        ]
+
+    def test_undeclared_attribute_and_unknown_action(self):
+        """Extra attributes are flagged; an undeclared action is an error."""
+        schema = CedarSchema(self.SCHEMA)
+
+        assert schema.validate_context('Action::"read"', {"mfa": True, "level": 1, "ip": "x"}) == [
+            "context.ip: attribute not declared in schema",
+        ]
+        with pytest.raises(ValueError, match="not declared"):
+            schema.validate_context('Action::"write"', {})
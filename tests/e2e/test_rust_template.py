@@ -0,0 +1,225 @@
+"""
+E2E tests for the raw CedarTemplate bindings.
+
+These exercise the Rust extension directly, so they need the
+extension to be built.
+"""
+
+import pytest
+from cedar_py._rust import CedarParseError, CedarPolicySet, CedarTemplate
+
+
+@pytest.mark.e2e
+class TestTemplateSlotCheckE2E:
+    """Detecting malformed slot usage."""
+
+    def test_well_formed_template_passes(self):
+        """A template using slots only in its scope has no problems."""
+        source = '@id("owner") permit(principal == ?principal, action, resource in ?resource);'
+
+        assert CedarTemplate.check(source) == []
+        template = CedarTemplate(source)
+        assert template.id == "owner"
+        assert template.slots() == ["?principal", "?resource"]
+
+    def test_context_slot_is_flagged(self):
+        """A slot named after context can never be linked."""
+        problems = CedarTemplate.check('permit(principal == ?context, action, resource);')
+
+        assert len(problems) == 1
+        assert "?context" in problems[0]
+
+    def test_slot_in_condition_is_flagged(self):
+        """Slots are only allowed in the scope, not in conditions."""
+        source = 'permit(principal, action, resource) when { context.owner == ?principal };'
+
+        assert CedarTemplate.check(source)
+        with pytest.raises(CedarParseError):
+            CedarTemplate(source)
+
+    def test_template_added_to_policy_set(self):
+        """Well-formed templates can be added to a policy set."""
+        policy_set = CedarPolicySet()
+
+        policy_set.add_template(CedarTemplate('@id("t") permit(principal == ?principal, action, resource);'))
+
+        with pytest.raises(ValueError, match="'t'"):
+            policy_set.add_template(CedarTemplate('@id("t") forbid(principal == ?principal, action, resource);'))
+
+
+@pytest.mark.e2e
+class TestTemplateLinksE2E:
+    """Inspecting a policy set's template links."""
+
+    def test_links_grouped_by_template(self):
+        """Both links of a template are listed with their slot values."""
+        policy_set = CedarPolicySet()
+        policy_set.add_template(CedarTemplate(
+            '@id("viewer") permit(principal == ?principal, action, resource in ?resource);'
+        ))
+        policy_set.add_template(CedarTemplate('@id("unused") permit(principal == ?principal, action, resource);'))
+        policy_set.link("viewer", "bob_docs", {"?principal": 'User::"bob"', "?resource": 'Folder::"docs"'})
+        policy_set.link("viewer", "alice_docs", {"?principal": 'User::"alice"', "?resource": 'Folder::"docs"'})
+
+        assert policy_set.links_by_template() == {
+            "unused": [],
+            "viewer": [
+                {"link_id": "alice_docs",
+                 "values": {"?principal": 'User::"alice"', "?resource": 'Folder::"docs"'}},
+                {"link_id": "bob_docs",
+                 "values": {"?principal": 'User::"bob"', "?resource": 'Folder::"docs"'}},
+            ],
+        }
+
+
+@pytest.mark.e2e
+class TestRelinkE2E:
+    """Retargeting an existing template link."""
+
+    @staticmethod
+    def linked_set():
+        policy_set = CedarPolicySet()
+        policy_set.add_template(CedarTemplate(
+            '@id("viewer") permit(principal == ?principal, action, resource);'
+        ))
+        policy_set.link("viewer", "doc_viewer", {"?principal": 'User::"alice"'})
+        return policy_set
+
+    def test_relink_changes_authorized_principal(self):
+        """After relinking, the new principal is allowed and the old one isn't."""
+        from cedar_py._rust import CedarAuthorizer
+
+        policy_set = self.linked_set()
+        authorizer = CedarAuthorizer()
+
+        policy_set.relink("doc_viewer", {"?principal": 'User::"bob"'})
+
+        assert authorizer.is_authorized(policy_set, 'User::"bob"', 'Action::"read"', 'Doc::"d"') is True
+        assert authorizer.is_authorized(policy_set, 'User::"alice"', 'Action::"read"', 'Doc::"d"') is False
+        assert len(policy_set) == 1
+
+    def test_unknown_link_raises(self):
+        """Relinking a missing id is an error."""
+        with pytest.raises(ValueError, match="missing"):
+            self.linked_set().relink("missing", {"?principal": 'User::"bob"'})
+
+    def test_failed_relink_keeps_original(self):
+        """Values that can't be linked leave the existing link in place."""
+        policy_set = self.linked_set()
+
+        with pytest.raises(ValueError):
+            policy_set.relink("doc_viewer", {"?resource": 'Doc::"d"'})
+
+        assert policy_set.links_by_template()["viewer"][0]["values"] == {"?principal": 'User::"alice"'}
+
+
+@pytest.mark.e2e
+class TestRequireAllLinkedE2E:
+    """Checking that every template is instantiated."""
+
+    def make_set(self):
+        """A set with two principal-slot templates."""
+        policy_set = CedarPolicySet()
+        policy_set.add_template(CedarTemplate('@id("viewer") permit(principal == ?principal, action, resource);'))
+        policy_set.add_template(CedarTemplate('@id("editor") permit(principal == ?principal, action, resource);'))
+        return policy_set
+
+    def test_unlinked_template_raises(self):
+        """A template with no links is named in the error."""
+        policy_set = self.make_set()
+        policy_set.link("viewer", "alice_view", {"?principal": 'User::"alice"'})
+
+        with pytest.raises(ValueError, match="editor") as excinfo:
+            policy_set.require_all_linked()
+        assert "viewer" not in str(excinfo.value)
+
+    def test_fully_linked_set_passes(self):
+        """Once every template has a link, the check passes."""
+        policy_set = self.make_set()
+        policy_set.link("viewer", "alice_view", {"?principal": 'User::"alice"'})
+        policy_set.link("editor", "bob_edit", {"?principal": 'User::"bob"'})
+
+        assert policy_set.require_all_linked() is None
+
+
+@pytest.mark.e2e
+class TestResolvedLinkedPoliciesE2E:
+    """Exporting links as standalone policy source."""
+
+    def test_resolved_source_has_concrete_uid(self):
+        """Each link's source names the linked uid, has no slots, and reloads under its link id."""
+        from cedar_py._rust import CedarPolicy
+
+        policy_set = CedarPolicySet()
+        policy_set.add_template(CedarTemplate(
+            '@id("viewer") permit(principal == ?principal, action == Action::"view", resource);'
+        ))
+        policy_set.add(CedarPolicy('@id("static") permit(principal, action, resource);'))
+        policy_set.link("viewer", "bob_view", {"?principal": 'User::"bob"'})
+        policy_set.link("viewer", "alice_view", {"?principal": 'User::"alice"'})
+
+        sources = policy_set.resolved_linked_policies()
+
+        assert len(sources) == 2
+        assert 'User::"alice"' in sources[0]
+        assert 'User::"bob"' in sources[1]
+        assert all("?principal" not in source for source in sources)
+        assert CedarPolicySet.from_string("\n".join(sources)).policy_ids() == ["alice_view", "bob_view"]
+
+
+@pytest.mark.e2e
+class TestLinkManyE2E:
+    """Linking a template many times in one call."""
+
+    def make_set(self):
+        """A set with one principal-slot template."""
+        policy_set = CedarPolicySet()
+        policy_set.add_template(CedarTemplate('@id("viewer") permit(principal == ?principal, action, resource);'))
+        return policy_set
+
+    def test_three_links_authorize(self):
+        """All three links are added, and each principal is allowed."""
+        from cedar_py._rust import CedarAuthorizer
+
+        policy_set = self.make_set()
+        names = ["alice", "bob", "carol"]
+
+        failures = policy_set.link_many("viewer", [
+            {"link_id": "%s_view" % name, "values": {"?principal": 'User::"%s"' % name}} for name in names
+        ])
+
+        assert failures == []
+        for name in names:
+            assert CedarAuthorizer().is_authorized(
+                policy_set, 'User::"%s"' % name, 'Action::"read"', 'Doc::"d"'
+            )
+
+    def test_failures_reported_per_entry(self):
+        """Bad entries are reported by index while good ones still link."""
+        policy_set = self.make_set()
+
+        failures = policy_set.link_many("viewer", [
+            {"link_id": "alice_view", "values": {"?principal": 'User::"alice"'}},
+            {"link_id": "alice_view", "values": {"?principal": 'User::"bob"'}},
+            {"values": {"?principal": 'User::"carol"'}},
+        ])
+
+        assert [(f["index"], f["link_id"]) for f in failures] == [(1, "alice_view"), (2, None)]
+        assert "alice_view" in failures[0]["error"]
+        assert policy_set.policy_ids() == ["alice_view"]
+
+
+@pytest.mark.e2e
+class TestSlotUsageE2E:
+    """Where a template's slots appear."""
+
+    def test_scope_locations(self):
+        """Each slot reports the scope and constraint it is used in."""
+        principal_only = CedarTemplate('permit(principal == ?principal, action, resource);')
+        both = CedarTemplate('permit(principal in ?principal, action, resource is Photo in ?resource);')
+
+        assert principal_only.slot_usage() == {"?principal": {"location": "principal_scope", "op": "eq"}}
+        assert both.slot_usage() == {
+            "?principal": {"location": "principal_scope", "op": "in"},
+            "?resource": {"location": "resource_scope", "op": "is_in"},
+        }